@@ -0,0 +1,337 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::console::ConsoleCheats;
+use crate::difficulty::Difficulty;
+use crate::game::{GameMode, GameState, GameplayEvent, GodMode, InRun};
+use crate::lasers_player::LasersPlayerStruct;
+use crate::levels::LevelState;
+use crate::stats::LevelStats;
+
+pub const ACHIEVEMENTS_FILE: &str = "achievements.ron";
+pub const TOAST_SECONDS: f32 = 4.0;
+
+pub struct AchievementDef {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub description: &'static str,
+    pub counter: Option<(&'static str, u32)>,
+}
+
+pub const ENEMIES_DESTROYED: &str = "enemies_destroyed";
+pub const LASERS_INTERCEPTED: &str = "lasers_intercepted";
+
+pub const ACHIEVEMENTS: &[AchievementDef] = &[
+    AchievementDef {
+        id: "first_blood",
+        title: "Перша кров",
+        description: "Знищіть першого ворога",
+        counter: Some((ENEMIES_DESTROYED, 1)),
+    },
+    AchievementDef {
+        id: "veteran",
+        title: "Ветеран",
+        description: "Знищіть 500 ворогів",
+        counter: Some((ENEMIES_DESTROYED, 500)),
+    },
+    AchievementDef {
+        id: "interceptor",
+        title: "Перехоплювач",
+        description: "Збийте 100 ворожих лазерів",
+        counter: Some((LASERS_INTERCEPTED, 100)),
+    },
+    AchievementDef {
+        id: "sharpshooter",
+        title: "Снайпер",
+        description: "Пройдіть рівень 1 без жодного промаху",
+        counter: None,
+    },
+    AchievementDef {
+        id: "victory",
+        title: "Переможець",
+        description: "Пройдіть усі три рівні",
+        counter: None,
+    },
+    AchievementDef {
+        id: "untouchable",
+        title: "Недоторканний",
+        description: "Виграйте гру, не отримавши жодного влучання",
+        counter: None,
+    },
+    AchievementDef {
+        id: "nightmare",
+        title: "Кошмар позаду",
+        description: "Виграйте гру на складності «кошмар»",
+        counter: None,
+    },
+];
+
+pub fn achievement(id: &str) -> Option<&'static AchievementDef> {
+    ACHIEVEMENTS.iter().find(|def| def.id == id)
+}
+
+#[derive(Serialize, Deserialize, Default, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct AchievementProgress {
+    pub unlocked: BTreeSet<String>,
+    pub counters: BTreeMap<String, u32>,
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct Achievements {
+    pub path: Option<PathBuf>,
+    pub progress: AchievementProgress,
+    pub run_hits: u32,
+    pub level_intercepts: u32,
+}
+
+impl Achievements {
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let progress = match std::fs::read(&path) {
+            Ok(bytes) => ron::de::from_bytes(&bytes).unwrap_or_else(|error| {
+                warn!("{}: {error}", path.display());
+                AchievementProgress::default()
+            }),
+            Err(_) => AchievementProgress::default(),
+        };
+        Self { path: Some(path), progress, ..default() }
+    }
+
+    pub fn is_unlocked(&self, id: &str) -> bool {
+        self.progress.unlocked.contains(id)
+    }
+
+    pub fn counter(&self, name: &str) -> u32 {
+        self.progress.counters.get(name).copied().unwrap_or(0)
+    }
+
+    pub fn unlock(&mut self, id: &str) -> bool {
+        achievement(id).is_some() && self.progress.unlocked.insert(id.to_string())
+    }
+
+    pub fn add(&mut self, name: &str, amount: u32) -> Vec<&'static str> {
+        let value = self.progress.counters.entry(name.to_string()).or_default();
+        *value += amount;
+        let value = *value;
+        ACHIEVEMENTS.iter()
+            .filter(|def| def.counter.is_some_and(|(counter, goal)| counter == name && value >= goal))
+            .filter(|def| self.unlock(def.id))
+            .map(|def| def.id)
+            .collect()
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let ron = ron::ser::to_string_pretty(&self.progress, ron::ser::PrettyConfig::default())
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
+        std::fs::write(path, ron)
+    }
+}
+
+#[derive(Message, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct AchievementUnlocked(pub &'static str);
+
+#[derive(Component)]
+pub struct AchievementToast(pub Timer);
+
+#[derive(Component)]
+pub struct AchievementsMenuText;
+
+pub struct AchievementsPlugin;
+
+impl Plugin for AchievementsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Achievements>()
+            .add_message::<AchievementUnlocked>()
+            .add_systems(OnEnter(InRun), reset_run_progress)
+            .add_systems(OnExit(InRun), save_achievements)
+            .add_systems(Update, (track_achievements, show_achievement_toasts, update_achievement_toasts).chain())
+            .add_systems(Update, open_achievements_menu.run_if(in_state(GameState::NotStarted)))
+            .add_systems(OnEnter(GameState::Achievements), show_achievements_menu)
+            .add_systems(Update, achievements_menu_keys.run_if(in_state(GameState::Achievements)));
+    }
+}
+
+pub fn reset_run_progress(mut achievements: ResMut<Achievements>) {
+    achievements.run_hits = 0;
+    achievements.level_intercepts = 0;
+}
+
+pub fn save_achievements(achievements: Res<Achievements>) {
+    if let Err(error) = achievements.save() {
+        warn!("{ACHIEVEMENTS_FILE}: {error}");
+    }
+}
+
+pub fn track_achievements(
+    mut events: MessageReader<GameplayEvent>,
+    mut unlocked: MessageWriter<AchievementUnlocked>,
+    mut achievements: ResMut<Achievements>,
+    stats: Res<LevelStats>,
+    mode: Res<GameMode>,
+    god: Res<GodMode>,
+    cheats: Res<ConsoleCheats>,
+    difficulty: Res<Difficulty>,
+    lasers: Query<(), With<LasersPlayerStruct>>,
+) {
+    if god.0 || cheats.run || !matches!(*mode, GameMode::Single | GameMode::Coop) {
+        events.clear();
+        return;
+    }
+    let mut new = Vec::new();
+    for event in events.read() {
+        match event {
+            GameplayEvent::EnemyDestroyed => new.extend(achievements.add(ENEMIES_DESTROYED, 1)),
+            GameplayEvent::EnemyLaserIntercepted => {
+                achievements.level_intercepts += 1;
+                new.extend(achievements.add(LASERS_INTERCEPTED, 1));
+            }
+            GameplayEvent::PlayerHit => achievements.run_hits += 1,
+            GameplayEvent::LevelCleared(level) => {
+                let in_flight = lasers.iter().len() as u32;
+                let missed = stats.shots_fired.saturating_sub(stats.hits + achievements.level_intercepts + in_flight);
+                let mut ids = Vec::new();
+                if *level == LevelState::Level1 && stats.shots_fired > 0 && missed == 0 {
+                    ids.push("sharpshooter");
+                }
+                if *level == LevelState::Level3 {
+                    ids.push("victory");
+                    if achievements.run_hits == 0 {
+                        ids.push("untouchable");
+                    }
+                    if *difficulty == Difficulty::Nightmare {
+                        ids.push("nightmare");
+                    }
+                }
+                new.extend(ids.into_iter().filter(|id| achievements.unlock(id)));
+                achievements.level_intercepts = 0;
+            }
+        }
+    }
+    if new.is_empty() {
+        return;
+    }
+    for id in new {
+        info!("achievement unlocked: {id}");
+        unlocked.write(AchievementUnlocked(id));
+    }
+    if let Err(error) = achievements.save() {
+        warn!("{ACHIEVEMENTS_FILE}: {error}");
+    }
+}
+
+pub fn show_achievement_toasts(
+    mut commands: Commands,
+    mut unlocked: MessageReader<AchievementUnlocked>,
+    asset_server: Res<AssetServer>,
+    toasts: Query<(), With<AchievementToast>>,
+) {
+    let mut shown = toasts.iter().len();
+    for AchievementUnlocked(id) in unlocked.read() {
+        let Some(def) = achievement(id) else {
+            continue;
+        };
+        commands.spawn((
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(60.0 + 60.0 * shown as f32),
+                right: Val::Px(10.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.1, 0.1, 0.2, 0.85)),
+            GlobalZIndex(8),
+            AchievementToast(Timer::from_seconds(TOAST_SECONDS, TimerMode::Once)),
+        )).with_children(|parent| {
+            parent.spawn((
+                Text::new(format!("Досягнення: {}\n{}", def.title, def.description)),
+                TextFont {
+                    font: asset_server.load("fonts/e-UkraineHead-Medium.otf"),
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.85, 0.3)),
+            ));
+        });
+        shown += 1;
+    }
+}
+
+pub fn update_achievement_toasts(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut toasts: Query<(&mut AchievementToast, Entity)>,
+) {
+    for (mut toast, entity) in toasts.iter_mut() {
+        if toast.0.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+pub fn achievements_menu_text(achievements: &Achievements) -> String {
+    let mut lines = vec![format!(
+        "Досягнення ({}/{})\n",
+        ACHIEVEMENTS.iter().filter(|def| achievements.is_unlocked(def.id)).count(),
+        ACHIEVEMENTS.len()
+    )];
+    for def in ACHIEVEMENTS {
+        let mark = if achievements.is_unlocked(def.id) { "x" } else { " " };
+        let mut line = format!("[{mark}] {} — {}", def.title, def.description);
+        if let Some((counter, goal)) = def.counter
+            && !achievements.is_unlocked(def.id) {
+            line += &format!(" ({}/{goal})", achievements.counter(counter).min(goal));
+        }
+        lines.push(line);
+    }
+    lines.push("\nEsc — назад".to_string());
+    lines.join("\n")
+}
+
+pub fn open_achievements_menu(
+    key_code: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if key_code.just_pressed(KeyCode::KeyT) {
+        next_state.set(GameState::Achievements);
+    }
+}
+
+pub fn show_achievements_menu(mut commands: Commands, asset_server: Res<AssetServer>, achievements: Res<Achievements>) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        DespawnOnExit(GameState::Achievements),
+    )).with_children(|parent| {
+        parent.spawn((
+            Text::new(achievements_menu_text(&achievements)),
+            TextFont {
+                font: asset_server.load("fonts/e-UkraineHead-Medium.otf"),
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            AchievementsMenuText,
+        ));
+    });
+}
+
+pub fn achievements_menu_keys(
+    key_code: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if key_code.just_pressed(KeyCode::Escape) || key_code.just_pressed(KeyCode::KeyT) {
+        next_state.set(GameState::NotStarted);
+    }
+}
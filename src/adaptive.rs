@@ -0,0 +1,234 @@
+use std::io::Write;
+use std::path::PathBuf;
+use bevy::prelude::*;
+use crate::console::*;
+use crate::difficulty::{Difficulty, DifficultyModifiers};
+use crate::game::{GameMode, GameState};
+use crate::stats::LevelStats;
+use crate::ui::NotStartedStruct;
+use crate::waves::WaveDirector;
+
+pub const ADAPTIVE_LOG_FILE: &str = "adaptive_log.csv";
+pub const ADAPTIVE_LOG_HEADER: &str = "level,difficulty,cleared,lives_lost,accuracy,seconds,rating,pressure_from,pressure_to";
+pub const PRESSURE_STEP: f32 = 0.1;
+pub const MIN_PRESSURE: f32 = 0.7;
+pub const MAX_PRESSURE: f32 = 1.5;
+pub const TARGET_ACCURACY: f32 = 0.5;
+pub const TARGET_CLEAR_SECONDS: f32 = 45.0;
+pub const CHALLENGE_BAND: f32 = 0.2;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct LevelPerformance {
+    pub level: u32,
+    pub cleared: bool,
+    pub lives_lost: u32,
+    pub accuracy: f32,
+    pub seconds: f32,
+}
+
+impl LevelPerformance {
+    pub fn rating(&self) -> f32 {
+        if !self.cleared {
+            return -1.0;
+        }
+        (self.accuracy - TARGET_ACCURACY)
+            + (TARGET_CLEAR_SECONDS - self.seconds) / TARGET_CLEAR_SECONDS * 0.5
+            - self.lives_lost as f32 * 0.5
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct AdaptiveAdjustment {
+    pub performance: LevelPerformance,
+    pub difficulty: Difficulty,
+    pub rating: f32,
+    pub from: f32,
+    pub to: f32,
+}
+
+impl AdaptiveAdjustment {
+    pub fn csv_line(&self) -> String {
+        let p = &self.performance;
+        format!(
+            "{},{},{},{},{:.2},{:.1},{:.2},{:.2},{:.2}",
+            p.level,
+            self.difficulty.name(),
+            p.cleared,
+            p.lives_lost,
+            p.accuracy,
+            p.seconds,
+            self.rating,
+            self.from,
+            self.to
+        )
+    }
+}
+
+#[derive(Resource)]
+pub struct AdaptiveDifficulty {
+    pub enabled: bool,
+    pub pressure: f32,
+    pub history: Vec<AdaptiveAdjustment>,
+    pub log_path: Option<PathBuf>,
+}
+
+impl Default for AdaptiveDifficulty {
+    fn default() -> Self {
+        Self { enabled: false, pressure: 1.0, history: Vec::new(), log_path: None }
+    }
+}
+
+impl AdaptiveDifficulty {
+    pub fn with_log(path: impl Into<PathBuf>) -> Self {
+        Self { log_path: Some(path.into()), ..default() }
+    }
+
+    pub fn adjust(&self, mut modifiers: DifficultyModifiers) -> DifficultyModifiers {
+        if self.enabled {
+            modifiers.enemy_count *= self.pressure;
+            modifiers.fire_interval /= self.pressure;
+            modifiers.projectile_speed *= self.pressure;
+        }
+        modifiers
+    }
+
+    pub fn observe(&mut self, difficulty: Difficulty, performance: LevelPerformance) -> AdaptiveAdjustment {
+        let rating = performance.rating();
+        let from = self.pressure;
+        let step = if rating > CHALLENGE_BAND {
+            PRESSURE_STEP
+        } else if rating < -CHALLENGE_BAND {
+            -PRESSURE_STEP
+        } else {
+            0.0
+        };
+        let to = (((from + step) * 100.0).round() / 100.0).clamp(MIN_PRESSURE, MAX_PRESSURE);
+        let adjustment = AdaptiveAdjustment { performance, difficulty, rating, from, to };
+        self.pressure = adjustment.to;
+        self.history.push(adjustment);
+        adjustment
+    }
+
+    pub fn append_log(&self, adjustment: &AdaptiveAdjustment) -> std::io::Result<()> {
+        let Some(path) = &self.log_path else {
+            return Ok(());
+        };
+        let new = !path.exists();
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+        if new {
+            writeln!(file, "{ADAPTIVE_LOG_HEADER}")?;
+        }
+        writeln!(file, "{}", adjustment.csv_line())
+    }
+}
+
+#[derive(Component)]
+pub struct AdaptiveText;
+
+pub struct AdaptivePlugin;
+
+impl Plugin for AdaptivePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<AdaptiveDifficulty>()
+            .add_systems(OnEnter(GameState::NotStarted), show_adaptive_settings)
+            .add_systems(Update, adaptive_keys.run_if(in_state(GameState::NotStarted)))
+            .add_systems(OnEnter(GameState::Intermission), observe_level)
+            .add_systems(OnEnter(GameState::GameOver), observe_level)
+            .add_systems(OnEnter(GameState::Win), observe_level)
+            .add_console_command(ConsoleCommand::new("adaptive", "adaptive [on|off] — адаптивна складність і її журнал", adaptive_command)
+                .with_arguments(&["on", "off"]));
+    }
+}
+
+fn adaptive_line(adaptive: &AdaptiveDifficulty) -> String {
+    if adaptive.enabled {
+        format!("G — адаптивна складність: увімк. (тиск ×{:.1})", adaptive.pressure)
+    } else {
+        "G — адаптивна складність: вимк.".to_string()
+    }
+}
+
+pub fn adaptive_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let mut adaptive = world.resource_mut::<AdaptiveDifficulty>();
+    match args.first().copied() {
+        Some("on") => adaptive.enabled = true,
+        Some("off") => adaptive.enabled = false,
+        Some(other) => return Err(format!("очікується on або off, отримано {other}")),
+        None => {}
+    }
+    let mut lines = vec![adaptive_line(&adaptive)];
+    lines.extend(adaptive.history.iter().map(AdaptiveAdjustment::csv_line));
+    Ok(lines.join("\n"))
+}
+
+pub fn show_adaptive_settings(
+    mut commands: Commands,
+    adaptive: Res<AdaptiveDifficulty>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            bottom: Val::Px(130.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        NotStartedStruct,
+        DespawnOnExit(GameState::NotStarted),
+    )).with_children(|p| {
+        p.spawn((
+            Text::new(adaptive_line(&adaptive)),
+            TextFont {
+                font: asset_server.load("fonts/e-ukrainehead-bold_w.ttf"),
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            AdaptiveText,
+        ));
+    });
+}
+
+pub fn adaptive_keys(
+    key_code: Res<ButtonInput<KeyCode>>,
+    mut adaptive: ResMut<AdaptiveDifficulty>,
+    mut text_query: Query<&mut Text, With<AdaptiveText>>,
+) {
+    if key_code.just_pressed(KeyCode::KeyG) {
+        adaptive.enabled = !adaptive.enabled;
+        for mut text in text_query.iter_mut() {
+            text.0 = adaptive_line(&adaptive);
+        }
+    }
+}
+
+pub fn observe_level(
+    time: Res<Time>,
+    state: Res<State<GameState>>,
+    stats: Res<LevelStats>,
+    director: Res<WaveDirector>,
+    mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
+    mut adaptive: ResMut<AdaptiveDifficulty>,
+) {
+    if !adaptive.enabled || !matches!(*mode, GameMode::Single | GameMode::Coop) {
+        return;
+    }
+    let Some(level) = director.level else {
+        return;
+    };
+    let performance = LevelPerformance {
+        level: level.number(),
+        cleared: *state.get() != GameState::GameOver,
+        lives_lost: stats.lives_lost,
+        accuracy: stats.accuracy(),
+        seconds: time.elapsed_secs() - stats.started_at,
+    };
+    let adjustment = adaptive.observe(*difficulty, performance);
+    info!("adaptive difficulty: {}", adjustment.csv_line());
+    if let Err(error) = adaptive.append_log(&adjustment) {
+        warn!("{ADAPTIVE_LOG_FILE}: {error}");
+    }
+}
@@ -0,0 +1,157 @@
+use bevy::prelude::*;
+use crate::game::{GameSet, GameState};
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AnimationMode {
+    Repeating,
+    Once,
+}
+
+#[derive(Component)]
+pub struct SpriteAnimation {
+    pub frames: Vec<usize>,
+    pub current: usize,
+    pub timer: Timer,
+    pub mode: AnimationMode,
+    pub finished: bool,
+}
+
+impl SpriteAnimation {
+    pub fn new(frames: Vec<usize>, frame_secs: f32, mode: AnimationMode) -> Self {
+        Self {
+            frames,
+            current: 0,
+            timer: Timer::from_seconds(frame_secs, TimerMode::Repeating),
+            mode,
+            finished: false,
+        }
+    }
+
+    pub fn starting_at(mut self, current: usize) -> Self {
+        self.current = current % self.frames.len().max(1);
+        self
+    }
+
+    pub fn frame(&self) -> usize {
+        self.frames.get(self.current).copied().unwrap_or(0)
+    }
+}
+
+#[derive(Component)]
+pub struct DespawnOnAnimationEnd;
+
+pub struct AnimationPlugin;
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<AnimationAtlases>()
+            .add_systems(Update, animate_sprites
+                .in_set(GameSet::Presentation)
+                .run_if(in_state(GameState::InGame)));
+    }
+}
+
+#[derive(Resource)]
+pub struct AnimationAtlases {
+    pub player_image: Handle<Image>,
+    pub player_layout: Handle<TextureAtlasLayout>,
+    pub enemy_image: Handle<Image>,
+    pub enemy_layout: Handle<TextureAtlasLayout>,
+    pub explosion_image: Handle<Image>,
+    pub explosion_layout: Handle<TextureAtlasLayout>,
+}
+
+pub const PLAYER_FRAME_LEFT: usize = 0;
+pub const PLAYER_FRAME_NEUTRAL: usize = 1;
+pub const PLAYER_FRAME_RIGHT: usize = 2;
+
+impl FromWorld for AnimationAtlases {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        let player_image = asset_server.load("images/players/playerSheet.png");
+        let enemy_image = asset_server.load("images/players/enemyShipSheet.png");
+        let explosion_image = asset_server.load("images/effects/explosion.png");
+
+        let mut layouts = world.resource_mut::<Assets<TextureAtlasLayout>>();
+        Self {
+            player_image,
+            player_layout: layouts.add(TextureAtlasLayout::from_grid(UVec2::new(50, 43), 3, 1, None, None)),
+            enemy_image,
+            enemy_layout: layouts.add(TextureAtlasLayout::from_grid(UVec2::new(70, 36), 4, 1, None, None)),
+            explosion_image,
+            explosion_layout: layouts.add(TextureAtlasLayout::from_grid(UVec2::new(64, 64), 8, 1, None, None)),
+        }
+    }
+}
+
+impl AnimationAtlases {
+    pub fn player_sprite(&self) -> Sprite {
+        Sprite::from_atlas_image(self.player_image.clone(), TextureAtlas {
+            layout: self.player_layout.clone(),
+            index: PLAYER_FRAME_NEUTRAL,
+        })
+    }
+
+    pub fn enemy_sprite(&self) -> Sprite {
+        Sprite::from_atlas_image(self.enemy_image.clone(), TextureAtlas {
+            layout: self.enemy_layout.clone(),
+            index: 0,
+        })
+    }
+
+    pub fn explosion_sprite(&self) -> Sprite {
+        Sprite::from_atlas_image(self.explosion_image.clone(), TextureAtlas {
+            layout: self.explosion_layout.clone(),
+            index: 0,
+        })
+    }
+}
+
+pub fn enemy_idle_animation(start: usize) -> SpriteAnimation {
+    SpriteAnimation::new(vec![0, 1, 2, 3, 2, 1], 0.12, AnimationMode::Repeating).starting_at(start)
+}
+
+pub fn explosion_animation() -> SpriteAnimation {
+    SpriteAnimation::new((0..8).collect(), 0.05, AnimationMode::Once)
+}
+
+pub fn bank_animation(frame: usize) -> SpriteAnimation {
+    SpriteAnimation::new(vec![frame, PLAYER_FRAME_NEUTRAL], 0.15, AnimationMode::Once)
+}
+
+pub fn animate_sprites(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut SpriteAnimation, &mut Sprite, Has<DespawnOnAnimationEnd>)>,
+) {
+    for (entity, mut animation, mut sprite, despawn) in query.iter_mut() {
+        if animation.finished {
+            continue;
+        }
+        animation.timer.tick(time.delta());
+        let steps = animation.timer.times_finished_this_tick() as usize;
+        if steps > 0 {
+            let len = animation.frames.len().max(1);
+            let next = animation.current + steps;
+            match animation.mode {
+                AnimationMode::Repeating => animation.current = next % len,
+                AnimationMode::Once if next >= len => {
+                    animation.current = len - 1;
+                    animation.finished = true;
+                }
+                AnimationMode::Once => animation.current = next,
+            }
+        }
+
+        if animation.finished && despawn {
+            commands.entity(entity).try_despawn();
+            continue;
+        }
+
+        let frame = animation.frame();
+        if let Some(atlas) = sprite.texture_atlas.as_mut() && atlas.index != frame {
+            atlas.index = frame;
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use bevy::prelude::*;
+use rand::{thread_rng, Rng};
+use crate::game::{GameSet, GameState};
+use crate::levels::LevelState;
+
+const BACKGROUND_HEIGHT: f32 = 801.0;
+const STARS_HEIGHT: f32 = 820.0;
+
+#[derive(Component)]
+pub struct BackgroundStruct;
+
+#[derive(Component)]
+pub struct StarStruct;
+
+#[derive(Component)]
+pub struct ParallaxLayer {
+    pub speed: f32,
+    pub wrap: f32,
+}
+
+pub struct BackgroundPlugin;
+
+impl Plugin for BackgroundPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ScrollSpeed>()
+            .add_systems(Startup, load_background_for_game)
+            .add_systems(OnEnter(LevelState::Level1), apply_level_background)
+            .add_systems(OnEnter(LevelState::Level2), apply_level_background)
+            .add_systems(OnEnter(LevelState::Level3), apply_level_background)
+            .add_systems(Update, scroll_background
+                .in_set(GameSet::Presentation)
+                .run_if(not(in_state(GameState::Pause))));
+    }
+}
+
+#[derive(Resource)]
+pub struct ScrollSpeed {
+    pub current: f32,
+    pub base: f32,
+    pub boost: f32,
+    pub boost_timer: Timer,
+}
+
+impl Default for ScrollSpeed {
+    fn default() -> Self {
+        let mut boost_timer = Timer::from_seconds(0.0, TimerMode::Once);
+        boost_timer.finish();
+        Self { current: 1.0, base: 1.0, boost: 1.0, boost_timer }
+    }
+}
+
+impl ScrollSpeed {
+    pub fn hyperspace(&mut self, boost: f32, secs: f32) {
+        self.boost = boost;
+        self.boost_timer = Timer::from_seconds(secs, TimerMode::Once);
+    }
+
+    pub fn target(&self) -> f32 {
+        if self.boost_timer.is_finished() { self.base } else { self.base * self.boost }
+    }
+}
+
+pub struct LevelBackground {
+    pub speed: f32,
+    pub tint: Color,
+}
+
+pub fn level_background(level: LevelState) -> LevelBackground {
+    match level {
+        LevelState::Level1 => LevelBackground { speed: 1.0, tint: Color::WHITE },
+        LevelState::Level2 => LevelBackground { speed: 1.4, tint: Color::srgb(0.85, 0.9, 1.0) },
+        LevelState::Level3 => LevelBackground { speed: 1.9, tint: Color::srgb(1.0, 0.8, 0.8) },
+    }
+}
+
+pub fn load_background_for_game(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let image = asset_server.load("images/backgrounds/Background_for_game.png");
+    for (i, flip_y) in [false, true].into_iter().enumerate() {
+        commands.spawn((
+            Sprite {
+                image: image.clone(),
+                flip_y,
+                ..default()
+            },
+            Transform::from_xyz(0.0, BACKGROUND_HEIGHT * i as f32, 0.0),
+            ParallaxLayer { speed: 15.0, wrap: BACKGROUND_HEIGHT * 2.0 },
+            BackgroundStruct
+        ));
+    }
+
+    let mut rng = thread_rng();
+    for (count, speed, size, brightness, z) in [
+        (70, 30.0, 1.0, 0.5, 0.1),
+        (40, 60.0, 2.0, 0.75, 0.2),
+        (20, 110.0, 3.0, 1.0, 0.3),
+    ] {
+        for _ in 0..count {
+            commands.spawn((
+                Sprite {
+                    color: Color::srgba(1.0, 1.0, 1.0, brightness),
+                    custom_size: Some(Vec2::splat(size)),
+                    ..default()
+                },
+                Transform::from_xyz(
+                    rng.gen_range(-500.0..500.0_f32),
+                    rng.gen_range(-STARS_HEIGHT / 2.0..STARS_HEIGHT / 2.0),
+                    z
+                ),
+                ParallaxLayer { speed, wrap: STARS_HEIGHT },
+                StarStruct
+            ));
+        }
+    }
+}
+
+pub fn apply_level_background(
+    level: Res<State<LevelState>>,
+    mut scroll: ResMut<ScrollSpeed>,
+    mut backgrounds: Query<&mut Sprite, With<BackgroundStruct>>,
+) {
+    let config = level_background(*level.get());
+    scroll.base = config.speed;
+    if *level.get() != LevelState::Level1 {
+        scroll.hyperspace(8.0, 1.5);
+    }
+    for mut sprite in backgrounds.iter_mut() {
+        sprite.color = config.tint;
+    }
+}
+
+pub fn scroll_background(
+    time: Res<Time>,
+    mut scroll: ResMut<ScrollSpeed>,
+    mut layers: Query<(&ParallaxLayer, &mut Transform, Has<StarStruct>)>,
+) {
+    let dt = time.delta_secs();
+    scroll.boost_timer.tick(time.delta());
+    let target = scroll.target();
+    scroll.current += (target - scroll.current) * (4.0 * dt).min(1.0);
+
+    let mut rng = thread_rng();
+    for (layer, mut transform, star) in layers.iter_mut() {
+        transform.translation.y -= layer.speed * scroll.current * dt;
+        if transform.translation.y < -layer.wrap / 2.0 {
+            transform.translation.y += layer.wrap;
+            if star {
+                transform.translation.x = rng.gen_range(-500.0..500.0_f32);
+            }
+        }
+        if star {
+            transform.scale.y = 1.0 + (scroll.current - scroll.base).max(0.0) * 2.0;
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use space_shooter::headless::*;
+
+fn main() {
+    let mut runs = 20;
+    let mut first_seed = 1;
+    let mut limit = 240.0;
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args.next().unwrap_or_default();
+        match flag.as_str() {
+            "--runs" => runs = value.parse().expect("--runs: очікується число"),
+            "--seed" => first_seed = value.parse().expect("--seed: очікується число"),
+            "--limit" => limit = value.parse().expect("--limit: очікується кількість секунд"),
+            _ => {
+                eprintln!("використання: balance [--runs 20] [--seed 1] [--limit 240]");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let mut results = Vec::new();
+    for seed in first_seed..first_seed + runs {
+        let run = balance_run(seed, limit);
+        println!(
+            "seed {:>4}: рівень {}, {}, {:.1} с",
+            run.seed,
+            run.reached,
+            if run.won { "перемога" } else { "поразка" },
+            run.seconds
+        );
+        results.push(run);
+    }
+
+    println!();
+    for level in balance_report(&results) {
+        println!(
+            "Рівень {}: пройдено {}/{} ({:.0}%)",
+            level.level,
+            level.clears,
+            level.attempts,
+            level.win_rate() * 100.0
+        );
+    }
+}
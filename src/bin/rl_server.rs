@@ -0,0 +1,32 @@
+use std::net::{Ipv4Addr, TcpListener};
+use std::thread;
+use space_shooter::rl::*;
+
+fn main() {
+    let mut port = DEFAULT_RL_PORT;
+    let mut args = std::env::args().skip(1);
+    while let Some(flag) = args.next() {
+        let value = args.next().unwrap_or_default();
+        match flag.as_str() {
+            "--port" => port = value.parse().expect("--port: некоректний порт"),
+            _ => {
+                eprintln!("використання: rl_server [--port {DEFAULT_RL_PORT}]");
+                std::process::exit(2);
+            }
+        }
+    }
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port)).expect("не вдалося відкрити порт");
+    println!("RL-середовище слухає {}", listener.local_addr().expect("адреса сокета"));
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        thread::spawn(move || {
+            let peer = stream.peer_addr().ok();
+            if let Err(error) = serve_connection(stream) {
+                eprintln!("{peer:?}: {error}");
+            }
+        });
+    }
+}
@@ -0,0 +1,200 @@
+use bevy::prelude::*;
+use crate::game::{keys, GameMode, GameState, InRun};
+use crate::lasers_enemies::LasersEnemiesStruct;
+use crate::levels::*;
+use crate::player::*;
+
+pub const ATTRACT_IDLE_SECONDS: f32 = 20.0;
+pub const BOT_THINK_SECONDS: f32 = 0.1;
+pub const BOT_RELOAD_SECONDS: f32 = 0.35;
+const DANGER_WIDTH: f32 = 32.0;
+const DANGER_HEIGHT: f32 = 320.0;
+const AIM_TOLERANCE: f32 = 20.0;
+const DODGE_SEARCH_STEPS: i32 = 6;
+
+#[derive(Component)]
+pub struct BotBrain {
+    pub think: Timer,
+    pub reload: Timer,
+}
+
+impl Default for BotBrain {
+    fn default() -> Self {
+        let mut reload = Timer::from_seconds(BOT_RELOAD_SECONDS, TimerMode::Once);
+        reload.almost_finish();
+        Self {
+            think: Timer::from_seconds(BOT_THINK_SECONDS, TimerMode::Repeating),
+            reload,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct AttractMode {
+    pub active: bool,
+    pub idle: Timer,
+}
+
+impl Default for AttractMode {
+    fn default() -> Self {
+        Self {
+            active: false,
+            idle: Timer::from_seconds(ATTRACT_IDLE_SECONDS, TimerMode::Once),
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct AttractText;
+
+pub struct BotPlugin;
+
+impl Plugin for BotPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<AttractMode>()
+            .add_systems(Update, drive_bots
+                .after(read_player_input)
+                .run_if(in_state(GameState::InGame)))
+            .add_systems(Update, attract_idle.run_if(in_state(GameState::NotStarted)))
+            .add_systems(Update, exit_attract
+                .after(keys)
+                .run_if(in_state(InRun).and(attract_active)))
+            .add_systems(OnEnter(InRun), show_attract_text.run_if(attract_active))
+            .add_systems(OnEnter(GameState::GameOver), end_attract.run_if(attract_active))
+            .add_systems(OnEnter(GameState::Win), end_attract.run_if(attract_active));
+    }
+}
+
+pub fn attract_active(attract: Res<AttractMode>) -> bool {
+    attract.active
+}
+
+fn danger(x: f32, y: f32, lasers: &[Vec2]) -> f32 {
+    lasers.iter()
+        .filter(|l| (l.x - x).abs() < DANGER_WIDTH && l.y > y - 10.0 && l.y - y < DANGER_HEIGHT)
+        .map(|l| DANGER_HEIGHT / (l.y - y + 30.0))
+        .sum()
+}
+
+pub fn bot_decision(x: f32, y: f32, enemies: &[Vec2], lasers: &[Vec2], can_fire: bool) -> PlayerInput {
+    let target = enemies.iter()
+        .min_by(|a, b| (a.x - x).abs().total_cmp(&(b.x - x).abs()))
+        .map(|e| e.x);
+    let toward = target.map_or(0.0, |t| (t - x).signum());
+
+    let here = danger(x, y, lasers);
+    let mut goal = x;
+    if here > 0.0 {
+        let mut best = (here, 0);
+        for k in -DODGE_SEARCH_STEPS..=DODGE_SEARCH_STEPS {
+            let c = (x + k as f32 * PLAYER_STEP).clamp(-PLAYER_BORDER, PLAYER_BORDER);
+            let score = (danger(c, y, lasers), k.abs());
+            if score.0 < best.0 || (score.0 == best.0 && score.1 < best.1) {
+                best = score;
+                goal = c;
+            }
+        }
+        goal = x + (goal - x).clamp(-PLAYER_STEP, PLAYER_STEP);
+    } else if let Some(t) = target
+        && (t - x).abs() > AIM_TOLERANCE / 2.0 {
+        let step = (x + toward * PLAYER_STEP).clamp(-PLAYER_BORDER, PLAYER_BORDER);
+        if danger(step, y, lasers) == 0.0 {
+            goal = step;
+        }
+    }
+
+    PlayerInput {
+        left: goal < x,
+        right: goal > x,
+        fire: can_fire && target.is_some_and(|t| (t - x).abs() < AIM_TOLERANCE),
+    }
+}
+
+pub fn drive_bots(
+    time: Res<Time>,
+    mut bots: Query<(&ControlScheme, &Transform, &mut PlayerInput, &mut BotBrain), ActivePlayer>,
+    enemies_l1: Query<&Transform, With<EnemiesStructInLevel1>>,
+    enemies_l2: Query<&Transform, With<EnemiesStructInLevel2>>,
+    enemies_l3: Query<&Transform, With<EnemiesStructInLevel3>>,
+    lasers: Query<&Transform, With<LasersEnemiesStruct>>,
+) {
+    let enemies: Vec<Vec2> = enemies_l1.iter().chain(enemies_l2.iter()).chain(enemies_l3.iter())
+        .map(|t| t.translation.truncate())
+        .collect();
+    let lasers: Vec<Vec2> = lasers.iter().map(|t| t.translation.truncate()).collect();
+
+    for (scheme, transform, mut input, mut brain) in bots.iter_mut() {
+        if *scheme != ControlScheme::Bot {
+            continue;
+        }
+        brain.reload.tick(time.delta());
+        if !brain.think.tick(time.delta()).just_finished() {
+            continue;
+        }
+        let position = transform.translation;
+        *input = bot_decision(position.x, position.y, &enemies, &lasers, brain.reload.is_finished());
+        if input.fire {
+            brain.reload.reset();
+        }
+    }
+}
+
+pub fn attract_idle(
+    time: Res<Time<Real>>,
+    key_code: Res<ButtonInput<KeyCode>>,
+    mut attract: ResMut<AttractMode>,
+    mut mode: ResMut<GameMode>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if key_code.get_just_pressed().next().is_some() {
+        attract.idle.reset();
+        return;
+    }
+    if attract.idle.tick(time.delta()).is_finished() {
+        attract.idle.reset();
+        attract.active = true;
+        *mode = GameMode::Bot;
+        next_state.set(GameState::InGame);
+    }
+}
+
+pub fn exit_attract(
+    key_code: Res<ButtonInput<KeyCode>>,
+    mut attract: ResMut<AttractMode>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if key_code.get_just_pressed().next().is_some() {
+        attract.active = false;
+        next_state.set(GameState::NotStarted);
+    }
+}
+
+pub fn end_attract(mut attract: ResMut<AttractMode>, mut next_state: ResMut<NextState<GameState>>) {
+    attract.active = false;
+    next_state.set(GameState::NotStarted);
+}
+
+pub fn show_attract_text(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            top: Val::Px(20.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        DespawnOnExit(InRun),
+    )).with_children(|p| {
+        p.spawn((
+            Text::new("ДЕМО — натисніть будь-яку клавішу"),
+            TextFont {
+                font: asset_server.load("fonts/e-UkraineHead-Medium.otf"),
+                font_size: 22.0,
+                ..default()
+            },
+            TextColor(Color::srgb(1.0, 0.85, 0.4)),
+            AttractText,
+        ));
+    });
+}
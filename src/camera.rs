@@ -1,5 +1,16 @@
 use bevy::prelude::*;
 
+#[derive(Component)]
+pub struct MainCamera;
+
+pub struct CameraPlugin;
+
+impl Plugin for CameraPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, camera_setup);
+    }
+}
+
 pub fn camera_setup(mut commands: Commands) {
-    commands.spawn(Camera2d::default());
+    commands.spawn((Camera2d, MainCamera));
 }
\ No newline at end of file
@@ -0,0 +1,315 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use bevy::ecs::message::MessageCursor;
+use bevy::input::InputSystems;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use crate::game::InRun;
+
+pub const CONSOLE_LOG_LIMIT: usize = 200;
+pub const CONSOLE_VISIBLE_LINES: usize = 12;
+
+pub type ConsoleRun = fn(&mut World, &[&str]) -> Result<String, String>;
+
+#[derive(Clone)]
+pub struct ConsoleCommand {
+    pub name: &'static str,
+    pub usage: &'static str,
+    pub arguments: &'static [&'static str],
+    pub cheat: bool,
+    pub run: ConsoleRun,
+}
+
+impl ConsoleCommand {
+    pub fn new(name: &'static str, usage: &'static str, run: ConsoleRun) -> Self {
+        Self { name, usage, arguments: &[], cheat: false, run }
+    }
+
+    pub fn cheat(mut self) -> Self {
+        self.cheat = true;
+        self
+    }
+
+    pub fn with_arguments(mut self, arguments: &'static [&'static str]) -> Self {
+        self.arguments = arguments;
+        self
+    }
+}
+
+#[derive(Resource, Default, Clone)]
+pub struct ConsoleCommands(pub BTreeMap<&'static str, ConsoleCommand>);
+
+#[derive(Resource, Default, Debug)]
+pub struct ConsoleCheats {
+    pub run: bool,
+    pub next_run: bool,
+}
+
+pub trait ConsoleAppExt {
+    fn add_console_command(&mut self, command: ConsoleCommand) -> &mut Self;
+}
+
+impl ConsoleAppExt for App {
+    fn add_console_command(&mut self, command: ConsoleCommand) -> &mut Self {
+        self.world_mut().get_resource_or_init::<ConsoleCommands>().0.insert(command.name, command);
+        self
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Console {
+    pub open: bool,
+    pub input: String,
+    pub history: Vec<String>,
+    pub browsing: Option<usize>,
+    pub log: Vec<String>,
+    pub queued: Vec<String>,
+}
+
+impl Console {
+    pub fn print(&mut self, text: &str) {
+        self.log.extend(text.lines().map(str::to_string));
+        let overflow = self.log.len().saturating_sub(CONSOLE_LOG_LIMIT);
+        self.log.drain(..overflow);
+    }
+
+    pub fn submit(&mut self, line: &str) {
+        let line = line.trim();
+        self.browsing = None;
+        if line.is_empty() {
+            return;
+        }
+        self.print(&format!("> {line}"));
+        if self.history.last().is_none_or(|last| last != line) {
+            self.history.push(line.to_string());
+        }
+        self.queued.push(line.to_string());
+    }
+
+    pub fn previous(&mut self) {
+        if self.history.is_empty() {
+            return;
+        }
+        let index = self.browsing.map_or(self.history.len() - 1, |i| i.saturating_sub(1));
+        self.browsing = Some(index);
+        self.input = self.history[index].clone();
+    }
+
+    pub fn next(&mut self) {
+        let Some(index) = self.browsing else {
+            return;
+        };
+        if index + 1 < self.history.len() {
+            self.browsing = Some(index + 1);
+            self.input = self.history[index + 1].clone();
+        } else {
+            self.browsing = None;
+            self.input.clear();
+        }
+    }
+
+    pub fn complete(&mut self, commands: &ConsoleCommands) {
+        let split = self.input.rfind(' ').map_or(0, |i| i + 1);
+        let (head, word) = self.input.split_at(split);
+        let candidates: Vec<&str> = if head.trim().is_empty() {
+            commands.0.keys().copied().filter(|name| name.starts_with(word)).collect()
+        } else {
+            let name = head.split_whitespace().next().unwrap_or_default();
+            commands.0.get(name)
+                .map(|command| command.arguments.iter().copied().filter(|a| a.starts_with(word)).collect())
+                .unwrap_or_default()
+        };
+        match candidates.as_slice() {
+            [] => {}
+            [only] => self.input = format!("{head}{only} "),
+            [first, rest @ ..] => {
+                let mut prefix = first.len();
+                for candidate in rest {
+                    prefix = first.chars()
+                        .zip(candidate.chars())
+                        .take_while(|(a, b)| a == b)
+                        .map(|(a, _)| a.len_utf8())
+                        .sum::<usize>()
+                        .min(prefix);
+                }
+                self.input = format!("{head}{}", &first[..prefix]);
+                let listing = candidates.join("  ");
+                self.print(&listing);
+            }
+        }
+    }
+}
+
+pub fn argument<T: FromStr>(args: &[&str], index: usize, name: &str) -> Result<T, String> {
+    let Some(arg) = args.get(index) else {
+        return Err(format!("не вказано {name}"));
+    };
+    arg.parse().map_err(|_| format!("некоректне значення {name}: {arg}"))
+}
+
+#[derive(Component)]
+pub struct ConsoleStruct;
+
+#[derive(Component)]
+pub struct ConsoleText;
+
+pub struct ConsolePlugin;
+
+impl Plugin for ConsolePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_message::<KeyboardInput>()
+            .init_resource::<Console>()
+            .init_resource::<ConsoleCommands>()
+            .init_resource::<ConsoleCheats>()
+            .add_console_command(ConsoleCommand::new("help", "help — список команд", help_command))
+            .add_console_command(ConsoleCommand::new("clear", "clear — очистити консоль", clear_command))
+            .add_systems(Startup, show_console)
+            .add_systems(PreUpdate, (edit_console, run_console_commands)
+                .chain()
+                .after(InputSystems))
+            .add_systems(Update, update_console_text);
+    }
+}
+
+pub fn help_command(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    let commands = world.resource::<ConsoleCommands>();
+    Ok(commands.0.values().map(|command| command.usage).collect::<Vec<_>>().join("\n"))
+}
+
+pub fn clear_command(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    world.resource_mut::<Console>().log.clear();
+    Ok(String::new())
+}
+
+pub fn run_console_line(world: &mut World, line: &str) -> Result<String, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let Some((name, args)) = words.split_first() else {
+        return Ok(String::new());
+    };
+    let (run, cheat) = world.get_resource::<ConsoleCommands>()
+        .and_then(|commands| commands.0.get(name))
+        .map(|command| (command.run, command.cheat))
+        .ok_or_else(|| format!("невідома команда {name}, введіть help"))?;
+    let output = run(world, args)?;
+    if cheat {
+        let in_run = world.contains_resource::<State<InRun>>();
+        let mut cheats = world.get_resource_or_init::<ConsoleCheats>();
+        if in_run {
+            cheats.run = true;
+        } else {
+            cheats.next_run = true;
+        }
+    }
+    Ok(output)
+}
+
+pub fn run_console_commands(world: &mut World) {
+    if world.resource::<Console>().queued.is_empty() {
+        return;
+    }
+    let queued = std::mem::take(&mut world.resource_mut::<Console>().queued);
+    for line in queued {
+        let output = run_console_line(world, &line).unwrap_or_else(|error| format!("Помилка: {error}"));
+        world.resource_mut::<Console>().print(&output);
+    }
+}
+
+pub fn edit_console(
+    mut key_code: ResMut<ButtonInput<KeyCode>>,
+    mut typed: ResMut<Messages<KeyboardInput>>,
+    mut cursor: Local<MessageCursor<KeyboardInput>>,
+    mut console: ResMut<Console>,
+    commands: Res<ConsoleCommands>,
+) {
+    let events: Vec<KeyboardInput> = cursor.read(&typed).filter(|e| e.state.is_pressed()).cloned().collect();
+    let toggled = key_code.just_pressed(KeyCode::Backquote)
+        || (console.open && key_code.just_pressed(KeyCode::Escape));
+    if toggled {
+        console.open = !console.open;
+    }
+    if !console.open && !toggled {
+        return;
+    }
+
+    if !toggled {
+        for event in events.iter().filter(|e| e.key_code != KeyCode::Backquote) {
+            match &event.logical_key {
+                Key::Character(c) => console.input.extend(c.chars().filter(|c| !c.is_control())),
+                Key::Space => console.input.push(' '),
+                _ => {}
+            }
+        }
+        if key_code.just_pressed(KeyCode::Backspace) {
+            console.input.pop();
+        }
+        if key_code.just_pressed(KeyCode::Tab) {
+            console.complete(&commands);
+        }
+        if key_code.just_pressed(KeyCode::ArrowUp) {
+            console.previous();
+        } else if key_code.just_pressed(KeyCode::ArrowDown) {
+            console.next();
+        }
+        if key_code.just_pressed(KeyCode::Enter) || key_code.just_pressed(KeyCode::NumpadEnter) {
+            let line = std::mem::take(&mut console.input);
+            console.submit(&line);
+        }
+    }
+
+    key_code.reset_all();
+    typed.clear();
+}
+
+pub fn show_console(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(0.0),
+            width: Val::Percent(100.0),
+            padding: UiRect::all(Val::Px(8.0)),
+            ..default()
+        },
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.8)),
+        GlobalZIndex(10),
+        Visibility::Hidden,
+        ConsoleStruct,
+    )).with_children(|parent| {
+        parent.spawn((
+            Text::new(""),
+            TextFont {
+                font: asset_server.load("fonts/e-UkraineHead-Medium.otf"),
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.7, 1.0, 0.7)),
+            ConsoleText,
+        ));
+    });
+}
+
+pub fn console_text(console: &Console) -> String {
+    let start = console.log.len().saturating_sub(CONSOLE_VISIBLE_LINES);
+    let mut text = console.log[start..].join("\n");
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    text.push_str(&format!("> {}_", console.input));
+    text
+}
+
+pub fn update_console_text(
+    console: Res<Console>,
+    mut panel_query: Query<&mut Visibility, With<ConsoleStruct>>,
+    mut text_query: Query<&mut Text, With<ConsoleText>>,
+) {
+    if !console.is_changed() {
+        return;
+    }
+    for mut visibility in panel_query.iter_mut() {
+        *visibility = if console.open { Visibility::Visible } else { Visibility::Hidden };
+    }
+    for mut text in text_query.iter_mut() {
+        text.0 = console_text(&console);
+    }
+}
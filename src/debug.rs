@@ -0,0 +1,216 @@
+use std::collections::VecDeque;
+use bevy::gizmos::config::GizmoConfigStore;
+use bevy::prelude::*;
+use crate::camera::MainCamera;
+use crate::console::*;
+use crate::game::GameState;
+use crate::intermission::Intermission;
+use crate::lasers_enemies::LasersEnemiesStruct;
+use crate::lasers_player::LasersPlayerStruct;
+use crate::levels::*;
+use crate::particles::Particle;
+use crate::physics::Collider;
+use crate::player::PlayerStruct;
+use crate::versus::CoverHealth;
+use crate::waves::WaveDirector;
+
+pub const FRAME_HISTORY: usize = 120;
+pub const GRAPH_SIZE: Vec2 = Vec2::new(240.0, 60.0);
+pub const GRAPH_CEILING: f32 = 1.0 / 30.0;
+
+pub const PLAYER_HITBOX: Color = Color::srgb(0.3, 1.0, 0.3);
+pub const ENEMY_HITBOX: Color = Color::srgb(1.0, 0.3, 0.3);
+pub const PLAYER_LASER_HITBOX: Color = Color::srgb(1.0, 1.0, 0.3);
+pub const ENEMY_LASER_HITBOX: Color = Color::srgb(1.0, 0.6, 0.1);
+pub const COVER_HITBOX: Color = Color::srgb(0.4, 0.6, 1.0);
+
+pub type HitboxKind = (Has<PlayerStruct>, Has<LasersPlayerStruct>, Has<LasersEnemiesStruct>, Has<CoverHealth>);
+
+#[derive(Resource, Default)]
+pub struct DebugOverlay {
+    pub open: bool,
+    pub frame_times: VecDeque<f32>,
+}
+
+impl DebugOverlay {
+    pub fn record(&mut self, frame_time: f32) {
+        self.frame_times.push_back(frame_time);
+        if self.frame_times.len() > FRAME_HISTORY {
+            self.frame_times.pop_front();
+        }
+    }
+
+    pub fn average_frame_time(&self) -> f32 {
+        if self.frame_times.is_empty() {
+            0.0
+        } else {
+            self.frame_times.iter().sum::<f32>() / self.frame_times.len() as f32
+        }
+    }
+
+    pub fn fps(&self) -> f32 {
+        let average = self.average_frame_time();
+        if average > 0.0 { 1.0 / average } else { 0.0 }
+    }
+}
+
+#[derive(Component)]
+pub struct DebugText;
+
+pub struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<DebugOverlay>()
+            .add_systems(Startup, show_debug_overlay)
+            .add_systems(Update, (toggle_debug_overlay, record_frame_time, update_debug_text).chain())
+            .add_systems(Update, (draw_hitboxes, draw_frame_graph)
+                .run_if(resource_exists::<GizmoConfigStore>)
+                .run_if(|overlay: Res<DebugOverlay>| overlay.open))
+            .add_console_command(ConsoleCommand::new("debug", "debug — показати або сховати налагоджувальну панель (F3)", debug_command));
+    }
+}
+
+pub fn debug_command(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    let mut overlay = world.resource_mut::<DebugOverlay>();
+    overlay.open = !overlay.open;
+    Ok(if overlay.open { "Панель налагодження увімкнено" } else { "Панель налагодження вимкнено" }.to_string())
+}
+
+pub fn toggle_debug_overlay(key_code: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<DebugOverlay>) {
+    if key_code.just_pressed(KeyCode::F3) {
+        overlay.open = !overlay.open;
+    }
+}
+
+pub fn record_frame_time(time: Res<Time<Real>>, mut overlay: ResMut<DebugOverlay>) {
+    overlay.bypass_change_detection().record(time.delta_secs());
+}
+
+pub fn show_debug_overlay(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font: asset_server.load("fonts/e-UkraineHead-Medium.otf"),
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(0.8, 1.0, 0.8)),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Px(10.0),
+            padding: UiRect::all(Val::Px(6.0)),
+            ..default()
+        },
+        GlobalZIndex(9),
+        Visibility::Hidden,
+        DebugText,
+    ));
+}
+
+pub fn update_debug_text(
+    overlay: Res<DebugOverlay>,
+    game_state: Res<State<GameState>>,
+    level_state: Option<Res<State<LevelState>>>,
+    shoot_timer: Res<EnemyShootTimer>,
+    director: Res<WaveDirector>,
+    intermission: Option<Res<Intermission>>,
+    players: Query<(), With<PlayerStruct>>,
+    enemies: Query<(), AnyEnemy>,
+    lasers_player: Query<(), With<LasersPlayerStruct>>,
+    lasers_enemies: Query<(), With<LasersEnemiesStruct>>,
+    particles: Query<(), With<Particle>>,
+    entities: Query<()>,
+    mut text_query: Query<(&mut Text, &mut Visibility), With<DebugText>>,
+) {
+    for (mut text, mut visibility) in text_query.iter_mut() {
+        *visibility = if overlay.open { Visibility::Visible } else { Visibility::Hidden };
+        if !overlay.open {
+            continue;
+        }
+        let level = level_state.as_ref().map_or("—".to_string(), |level| format!("{:?}", level.get()));
+        let mut lines = vec![
+            format!("FPS: {:.0} ({:.1} мс)", overlay.fps(), overlay.average_frame_time() * 1000.0),
+            format!("Стан: {:?} / {}", game_state.get(), level),
+            format!(
+                "Гравці: {}  Вороги: {}  Лазери гравців: {}  Лазери ворогів: {}",
+                players.iter().len(),
+                enemies.iter().len(),
+                lasers_player.iter().len(),
+                lasers_enemies.iter().len(),
+            ),
+            format!("Частинки: {}  Усього сутностей: {}", particles.iter().len(), entities.iter().len()),
+            format!(
+                "EnemyShootTimer: {:.2} / {:.2} с",
+                shoot_timer.0.elapsed_secs(),
+                shoot_timer.0.duration().as_secs_f32()
+            ),
+            format!(
+                "WaveDirector: хвиля {}/{}, {:.1} с, в черзі {}",
+                director.next_wave,
+                director.waves.len(),
+                director.elapsed,
+                director.pending.len()
+            ),
+        ];
+        if let Some(intermission) = &intermission {
+            lines.push(format!(
+                "Intermission: {:.1} / {:.1} с",
+                intermission.summary.elapsed_secs() + intermission.countdown.elapsed_secs(),
+                intermission.summary.duration().as_secs_f32() + intermission.countdown.duration().as_secs_f32()
+            ));
+        }
+        text.0 = lines.join("\n");
+    }
+}
+
+pub fn draw_hitboxes(
+    mut gizmos: Gizmos,
+    colliders: Query<(&Transform, &Collider, HitboxKind)>,
+) {
+    for (transform, collider, (player, laser_player, laser_enemy, cover)) in colliders.iter() {
+        let color = if player {
+            PLAYER_HITBOX
+        } else if laser_player {
+            PLAYER_LASER_HITBOX
+        } else if laser_enemy {
+            ENEMY_LASER_HITBOX
+        } else if cover {
+            COVER_HITBOX
+        } else {
+            ENEMY_HITBOX
+        };
+        let center = transform.translation.truncate() + collider.offset;
+        gizmos.rect_2d(Isometry2d::from_translation(center), collider.half_size * 2.0, color);
+    }
+}
+
+pub fn draw_frame_graph(
+    mut gizmos: Gizmos,
+    overlay: Res<DebugOverlay>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+) {
+    let Ok((camera, camera_tr)) = camera_query.single() else {
+        return;
+    };
+    let Some(viewport) = camera.logical_viewport_size() else {
+        return;
+    };
+    let Ok(corner) = camera.viewport_to_world_2d(camera_tr, viewport - Vec2::splat(10.0)) else {
+        return;
+    };
+    let origin = corner - Vec2::new(GRAPH_SIZE.x, 0.0);
+    gizmos.rect_2d(Isometry2d::from_translation(origin + GRAPH_SIZE / 2.0), GRAPH_SIZE, Color::srgba(1.0, 1.0, 1.0, 0.4));
+
+    let target = origin.y + GRAPH_SIZE.y * (1.0 / 60.0) / GRAPH_CEILING;
+    gizmos.line_2d(Vec2::new(origin.x, target), Vec2::new(origin.x + GRAPH_SIZE.x, target), Color::srgba(0.3, 1.0, 0.3, 0.4));
+
+    let step = GRAPH_SIZE.x / (FRAME_HISTORY - 1) as f32;
+    let points = overlay.frame_times.iter().enumerate().map(|(i, frame_time)| {
+        Vec2::new(origin.x + i as f32 * step, origin.y + GRAPH_SIZE.y * (frame_time / GRAPH_CEILING).min(1.0))
+    });
+    gizmos.linestrip_2d(points, Color::srgb(1.0, 0.9, 0.3));
+}
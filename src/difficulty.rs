@@ -0,0 +1,312 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::adaptive::AdaptiveDifficulty;
+use crate::console::*;
+use crate::game::{GameMode, GameState, InRun};
+use crate::levels::EnemyShootTimer;
+use crate::stats::Score;
+use crate::tuning::{GameTuning, apply_tuning};
+use crate::ui::NotStartedStruct;
+use crate::waves::{ENTRY_STAGGER, Wave};
+
+pub const HIGH_SCORES_FILE: &str = "highscores.ron";
+pub const HIGH_SCORE_LIMIT: usize = 5;
+
+#[derive(Resource, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Nightmare,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct DifficultyModifiers {
+    pub enemy_count: f32,
+    pub fire_interval: f32,
+    pub projectile_speed: f32,
+    pub lives: u32,
+    pub score: f32,
+}
+
+impl DifficultyModifiers {
+    pub fn scale_waves(&self, waves: &[usize]) -> Vec<usize> {
+        waves.iter().map(|&count| ((count as f32 * self.enemy_count).round() as usize).max(1)).collect()
+    }
+
+    pub fn scale_layout(&self, mut waves: Vec<Wave>) -> Vec<Wave> {
+        for wave in waves.iter_mut().filter(|wave| !wave.enemies.is_empty()) {
+            let authored = wave.enemies.len();
+            let count = self.scale_waves(&[authored])[0];
+            wave.enemies.truncate(count);
+            let last_delay = wave.enemies.iter().map(|spawn| spawn.delay).fold(0.0, f32::max);
+            for i in authored..count {
+                let mut copy = wave.enemies[i % authored].clone();
+                copy.x = -copy.x;
+                copy.delay = last_delay + (i - authored + 1) as f32 * ENTRY_STAGGER;
+                wave.enemies.push(copy);
+            }
+        }
+        waves
+    }
+
+    pub fn shoot_interval(&self, tuning: &GameTuning) -> f32 {
+        tuning.enemy_shoot_interval * self.fire_interval
+    }
+
+    pub fn laser_speed(&self, tuning: &GameTuning) -> f32 {
+        tuning.enemy_laser_speed * self.projectile_speed
+    }
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Nightmare];
+
+    pub fn modifiers(&self) -> DifficultyModifiers {
+        match self {
+            Difficulty::Easy => DifficultyModifiers {
+                enemy_count: 0.6,
+                fire_interval: 1.5,
+                projectile_speed: 0.75,
+                lives: 3,
+                score: 0.5,
+            },
+            Difficulty::Normal => DifficultyModifiers {
+                enemy_count: 1.0,
+                fire_interval: 1.0,
+                projectile_speed: 1.0,
+                lives: 1,
+                score: 1.0,
+            },
+            Difficulty::Hard => DifficultyModifiers {
+                enemy_count: 1.3,
+                fire_interval: 0.75,
+                projectile_speed: 1.2,
+                lives: 1,
+                score: 1.5,
+            },
+            Difficulty::Nightmare => DifficultyModifiers {
+                enemy_count: 1.6,
+                fire_interval: 0.5,
+                projectile_speed: 1.4,
+                lives: 1,
+                score: 2.5,
+            },
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|d| d == self).unwrap_or(0);
+        Self::ALL[(index + 1).min(Self::ALL.len() - 1)]
+    }
+
+    pub fn previous(&self) -> Self {
+        let index = Self::ALL.iter().position(|d| d == self).unwrap_or(0);
+        Self::ALL[index.saturating_sub(1)]
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "легка",
+            Difficulty::Normal => "нормальна",
+            Difficulty::Hard => "складна",
+            Difficulty::Nightmare => "кошмар",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Nightmare => "nightmare",
+        }
+    }
+
+    pub fn points(&self, points: u32) -> u32 {
+        (points as f32 * self.modifiers().score).round() as u32
+    }
+}
+
+#[derive(SystemParam)]
+pub struct Challenge<'w> {
+    difficulty: Res<'w, Difficulty>,
+    adaptive: Res<'w, AdaptiveDifficulty>,
+}
+
+impl Challenge<'_> {
+    pub fn modifiers(&self) -> DifficultyModifiers {
+        self.adaptive.adjust(self.difficulty.modifiers())
+    }
+
+    pub fn is_changed(&self) -> bool {
+        self.difficulty.is_changed() || self.adaptive.is_changed()
+    }
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct HighScores {
+    pub path: Option<PathBuf>,
+    pub boards: BTreeMap<Difficulty, Vec<u32>>,
+}
+
+impl HighScores {
+    pub fn load(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let boards = match std::fs::read(&path) {
+            Ok(bytes) => ron::de::from_bytes(&bytes).unwrap_or_else(|error| {
+                warn!("{}: {error}", path.display());
+                BTreeMap::new()
+            }),
+            Err(_) => BTreeMap::new(),
+        };
+        Self { path: Some(path), boards }
+    }
+
+    pub fn best(&self, difficulty: Difficulty) -> &[u32] {
+        self.boards.get(&difficulty).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn record(&mut self, difficulty: Difficulty, score: u32) -> Option<usize> {
+        if score == 0 {
+            return None;
+        }
+        let board = self.boards.entry(difficulty).or_default();
+        let rank = board.iter().position(|&best| score > best).unwrap_or(board.len());
+        if rank >= HIGH_SCORE_LIMIT {
+            return None;
+        }
+        board.insert(rank, score);
+        board.truncate(HIGH_SCORE_LIMIT);
+        Some(rank)
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let ron = ron::ser::to_string_pretty(&self.boards, ron::ser::PrettyConfig::default())
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
+        std::fs::write(path, ron)
+    }
+}
+
+#[derive(Component)]
+pub struct DifficultyText;
+
+pub struct DifficultyPlugin;
+
+impl Plugin for DifficultyPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Difficulty>()
+            .init_resource::<HighScores>()
+            .add_systems(PreUpdate, apply_shoot_interval.after(apply_tuning))
+            .add_systems(OnEnter(GameState::NotStarted), show_difficulty_settings)
+            .add_systems(Update, difficulty_keys.run_if(in_state(GameState::NotStarted)))
+            .add_systems(OnEnter(GameState::GameOver), record_high_score)
+            .add_systems(OnEnter(GameState::Win), record_high_score)
+            .add_console_command(ConsoleCommand::new("difficulty", "difficulty <easy|normal|hard|nightmare> — складність наступного забігу", difficulty_command)
+                .with_arguments(&["easy", "normal", "hard", "nightmare"])
+                .cheat());
+    }
+}
+
+pub fn difficulty_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let name: String = argument(args, 0, "складність")?;
+    let Some(difficulty) = Difficulty::ALL.into_iter().find(|d| d.name() == name) else {
+        return Err(format!("невідома складність {name}"));
+    };
+    if world.contains_resource::<State<InRun>>() {
+        return Err("складність можна змінити лише поза забігом".to_string());
+    }
+    world.insert_resource(difficulty);
+    Ok(format!("Складність: {}", difficulty.label()))
+}
+
+pub fn apply_shoot_interval(challenge: Challenge, tuning: Res<GameTuning>, mut timer: ResMut<EnemyShootTimer>) {
+    if challenge.is_changed() || tuning.is_changed() {
+        let interval = challenge.modifiers().shoot_interval(&tuning);
+        timer.0.set_duration(Duration::from_secs_f32(interval));
+    }
+}
+
+pub fn difficulty_line(difficulty: Difficulty, scores: &HighScores) -> String {
+    let best = scores.best(difficulty);
+    let records = if best.is_empty() {
+        "немає".to_string()
+    } else {
+        best.iter().map(u32::to_string).collect::<Vec<_>>().join(", ")
+    };
+    format!("←/→ — складність: {} (рекорди: {})", difficulty.label(), records)
+}
+
+pub fn show_difficulty_settings(
+    mut commands: Commands,
+    difficulty: Res<Difficulty>,
+    scores: Res<HighScores>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            bottom: Val::Px(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        NotStartedStruct,
+        DespawnOnExit(GameState::NotStarted),
+    )).with_children(|p| {
+        p.spawn((
+            Text::new(difficulty_line(*difficulty, &scores)),
+            TextFont {
+                font: asset_server.load("fonts/e-ukrainehead-bold_w.ttf"),
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            DifficultyText,
+        ));
+    });
+}
+
+pub fn difficulty_keys(
+    key_code: Res<ButtonInput<KeyCode>>,
+    mut difficulty: ResMut<Difficulty>,
+    scores: Res<HighScores>,
+    mut text_query: Query<&mut Text, With<DifficultyText>>,
+) {
+    if key_code.just_pressed(KeyCode::ArrowRight) {
+        *difficulty = difficulty.next();
+    } else if key_code.just_pressed(KeyCode::ArrowLeft) {
+        *difficulty = difficulty.previous();
+    }
+    if difficulty.is_changed() {
+        for mut text in text_query.iter_mut() {
+            text.0 = difficulty_line(*difficulty, &scores);
+        }
+    }
+}
+
+pub fn record_high_score(
+    score: Res<Score>,
+    difficulty: Res<Difficulty>,
+    mode: Res<GameMode>,
+    adaptive: Res<AdaptiveDifficulty>,
+    cheats: Res<ConsoleCheats>,
+    mut scores: ResMut<HighScores>,
+) {
+    if adaptive.enabled || cheats.run || !matches!(*mode, GameMode::Single | GameMode::Coop) {
+        return;
+    }
+    if scores.record(*difficulty, score.0).is_some()
+        && let Err(error) = scores.save() {
+        warn!("{HIGH_SCORES_FILE}: {error}");
+    }
+}
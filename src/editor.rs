@@ -0,0 +1,375 @@
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use crate::animation::*;
+use crate::camera::MainCamera;
+use crate::game::{GameMode, GameState};
+use crate::level_file::*;
+use crate::levels::*;
+use crate::waves::*;
+
+pub const DEFAULT_LEVEL_PATH: &str = "levels/custom.level.ron";
+pub const EDITOR_SNAP: f32 = 10.0;
+pub const EDITOR_REMOVE_DISTANCE: f32 = 35.0;
+pub const EDITOR_DELAY_STEP: f32 = 0.5;
+pub const EDITOR_AREA: Rect = Rect {
+    min: Vec2::new(-470.0, -150.0),
+    max: Vec2::new(470.0, 370.0),
+};
+
+#[derive(Resource, Clone, PartialEq, Debug, Default)]
+pub struct EditorConfig {
+    pub open: bool,
+    pub levels: Vec<String>,
+}
+
+impl EditorConfig {
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<(Self, Vec<String>), String> {
+        let mut config = Self::default();
+        let mut rest = Vec::new();
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            match flag.as_str() {
+                "--editor" => config.open = true,
+                "--level" => config.levels.push(args.next().ok_or("--level потребує шлях до файлу".to_string())?),
+                _ => rest.push(flag),
+            }
+        }
+        Ok((config, rest))
+    }
+}
+
+#[derive(Resource)]
+pub struct LevelEditor {
+    pub path: String,
+    pub level: LevelFile,
+    pub wave: usize,
+    pub brush: EnemySpawn,
+    pub status: String,
+    pub previewing: bool,
+}
+
+impl Default for LevelEditor {
+    fn default() -> Self {
+        Self {
+            path: DEFAULT_LEVEL_PATH.to_string(),
+            level: LevelFile::default(),
+            wave: 0,
+            brush: EnemySpawn::at(0.0, 0.0),
+            status: String::new(),
+            previewing: false,
+        }
+    }
+}
+
+impl LevelEditor {
+    pub fn current_wave(&mut self) -> &mut Wave {
+        while self.level.waves.len() <= self.wave {
+            let first = self.level.waves.is_empty();
+            self.level.waves.push(Wave {
+                cleared: (!first).then_some(WAVE_CLEARED_FRACTION),
+                ..default()
+            });
+        }
+        &mut self.level.waves[self.wave]
+    }
+
+    pub fn next_wave(&mut self) {
+        let last = self.level.waves.len().saturating_sub(1);
+        let current_empty = self.level.waves.get(self.wave).is_none_or(|w| w.enemies.is_empty());
+        self.wave = if self.wave < last || !current_empty { self.wave + 1 } else { 0 };
+    }
+
+    pub fn place(&mut self, position: Vec2) {
+        let position = (position / EDITOR_SNAP).round() * EDITOR_SNAP;
+        if !EDITOR_AREA.contains(position) {
+            return;
+        }
+        let brush = self.brush.clone();
+        self.current_wave().enemies.push(EnemySpawn {
+            x: position.x,
+            y: position.y,
+            ..brush
+        });
+    }
+
+    pub fn remove_near(&mut self, position: Vec2) -> bool {
+        let Some(wave) = self.level.waves.get_mut(self.wave) else {
+            return false;
+        };
+        let enemies = &mut wave.enemies;
+        let nearest = enemies.iter()
+            .enumerate()
+            .map(|(i, e)| (i, Vec2::new(e.x, e.y).distance(position)))
+            .filter(|(_, d)| *d < EDITOR_REMOVE_DISTANCE)
+            .min_by(|a, b| a.1.total_cmp(&b.1));
+        match nearest {
+            Some((i, _)) => {
+                enemies.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn save(&mut self) {
+        self.status = match self.level.write(&asset_file_path(&self.path)) {
+            Ok(()) => format!("Збережено: {}", self.path),
+            Err(error) => error.to_string(),
+        };
+    }
+
+    pub fn load(&mut self) {
+        self.status = match LevelFile::read(&asset_file_path(&self.path)) {
+            Ok(level) => {
+                self.level = level;
+                self.wave = 0;
+                format!("Завантажено: {}", self.path)
+            }
+            Err(error) => error.to_string(),
+        };
+    }
+}
+
+#[derive(Component)]
+pub struct EditorMarker;
+
+#[derive(Component)]
+pub struct EditorHudText;
+
+pub struct EditorPlugin;
+
+impl Plugin for EditorPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<LevelEditor>()
+            .add_systems(Startup, apply_editor_config)
+            .add_systems(Update, open_editor.run_if(in_state(GameState::NotStarted)))
+            .add_systems(OnEnter(GameState::Editor), show_editor)
+            .add_systems(Update, (editor_mouse, editor_keys, sync_editor_markers, update_editor_hud)
+                .chain()
+                .run_if(in_state(GameState::Editor)))
+            .add_systems(OnEnter(GameState::GameOver), end_preview.run_if(previewing))
+            .add_systems(OnEnter(GameState::Win), end_preview.run_if(previewing))
+            .add_systems(OnEnter(GameState::Intermission), end_preview.run_if(previewing));
+    }
+}
+
+pub fn previewing(editor: Res<LevelEditor>) -> bool {
+    editor.previewing
+}
+
+pub fn apply_editor_config(
+    config: Option<Res<EditorConfig>>,
+    asset_server: Res<AssetServer>,
+    mut files: ResMut<LevelFiles>,
+    mut editor: ResMut<LevelEditor>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(config) = config else {
+        return;
+    };
+    if let Some(path) = config.levels.first() {
+        files.levels = config.levels.iter().map(|path| asset_server.load(path.clone())).collect();
+        editor.path = path.clone();
+        editor.load();
+    }
+    if config.open {
+        next_state.set(GameState::Editor);
+    }
+}
+
+pub fn open_editor(
+    key_code: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if key_code.just_pressed(KeyCode::KeyE) {
+        next_state.set(GameState::Editor);
+    }
+}
+
+pub fn show_editor(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut editor: ResMut<LevelEditor>,
+    mut files: ResMut<LevelFiles>,
+) {
+    files.preview = None;
+    editor.set_changed();
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font: asset_server.load("fonts/e-UkraineHead-Medium.otf"),
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+        EditorHudText,
+        DespawnOnExit(GameState::Editor),
+    ));
+}
+
+pub fn editor_mouse(
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut editor: ResMut<LevelEditor>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) && !mouse.just_pressed(MouseButton::Right) {
+        return;
+    }
+    let (Ok(window), Ok((camera, camera_transform))) = (windows.single(), cameras.single()) else {
+        return;
+    };
+    let Some(position) = window.cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok()) else {
+        return;
+    };
+    if mouse.just_pressed(MouseButton::Left) {
+        editor.place(position);
+    } else {
+        editor.remove_near(position);
+    }
+}
+
+pub fn editor_keys(
+    key_code: Res<ButtonInput<KeyCode>>,
+    mut editor: ResMut<LevelEditor>,
+    mut files: ResMut<LevelFiles>,
+    mut levels: ResMut<Assets<LevelFile>>,
+    mut mode: ResMut<GameMode>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if key_code.just_pressed(KeyCode::Escape) {
+        next_state.set(GameState::NotStarted);
+        return;
+    }
+    if key_code.just_pressed(KeyCode::KeyW) {
+        editor.next_wave();
+    }
+    if key_code.just_pressed(KeyCode::KeyE) {
+        let wave = editor.current_wave();
+        wave.entry = wave.entry.next();
+    }
+    if key_code.just_pressed(KeyCode::KeyK) {
+        editor.brush.kind = editor.brush.kind.next();
+    }
+    if key_code.just_pressed(KeyCode::KeyM) {
+        editor.brush.movement = editor.brush.movement.next();
+    }
+    if key_code.just_pressed(KeyCode::KeyF) {
+        editor.brush.fire = editor.brush.fire.next();
+    }
+    if key_code.just_pressed(KeyCode::ArrowUp) {
+        editor.brush.delay += EDITOR_DELAY_STEP;
+    }
+    if key_code.just_pressed(KeyCode::ArrowDown) {
+        editor.brush.delay = (editor.brush.delay - EDITOR_DELAY_STEP).max(0.0);
+    }
+    if key_code.just_pressed(KeyCode::Backspace) {
+        let wave = editor.wave;
+        if let Some(wave) = editor.level.waves.get_mut(wave) {
+            wave.enemies.pop();
+        }
+    }
+    if key_code.just_pressed(KeyCode::Delete) {
+        editor.level.waves.clear();
+        editor.wave = 0;
+    }
+    if key_code.just_pressed(KeyCode::KeyS) {
+        editor.save();
+    }
+    if key_code.just_pressed(KeyCode::KeyL) {
+        editor.load();
+    }
+    if key_code.just_pressed(KeyCode::KeyP) {
+        if editor.level.enemy_count() == 0 {
+            editor.status = "Рівень порожній — розставте ворогів".to_string();
+            return;
+        }
+        files.preview = Some(levels.add(editor.level.clone()));
+        editor.previewing = true;
+        *mode = GameMode::Single;
+        next_state.set(GameState::InGame);
+    }
+}
+
+pub fn sync_editor_markers(
+    mut commands: Commands,
+    editor: Res<LevelEditor>,
+    atlases: Res<AnimationAtlases>,
+    markers: Query<Entity, With<EditorMarker>>,
+) {
+    if !editor.is_changed() {
+        return;
+    }
+    for marker in markers.iter() {
+        commands.entity(marker).despawn();
+    }
+    let spawns = editor.level.waves.iter().enumerate()
+        .flat_map(|(wave, w)| w.enemies.iter().map(move |spawn| (wave, spawn)));
+    for (wave, spawn) in spawns {
+        let mut sprite = atlases.enemy_sprite();
+        let color = if spawn.kind == EnemyKind::Armored { ARMORED_TINT } else { Color::WHITE };
+        sprite.color = color.with_alpha(if wave == editor.wave { 1.0 } else { 0.3 });
+        commands.spawn((
+            sprite,
+            Transform::from_xyz(spawn.x, spawn.y, 0.5),
+            EditorMarker,
+            DespawnOnExit(GameState::Editor),
+        )).with_children(|p| {
+            p.spawn((
+                Text2d::new(format!("{}: {:.1} с", wave + 1, spawn.delay)),
+                TextFont {
+                    font_size: 12.0,
+                    ..default()
+                },
+                Transform::from_xyz(0.0, -28.0, 0.1),
+            ));
+        });
+    }
+}
+
+pub fn update_editor_hud(editor: Res<LevelEditor>, mut text_query: Query<&mut Text, With<EditorHudText>>) {
+    if !editor.is_changed() {
+        return;
+    }
+    let brush = &editor.brush;
+    let (entry, trigger) = editor.level.waves.get(editor.wave).map_or((EntryPath::default(), String::new()), |w| {
+        let mut trigger = Vec::new();
+        if let Some(at) = w.at {
+            trigger.push(format!("на {at:.0} с"));
+        }
+        if let Some(cleared) = w.cleared {
+            trigger.push(format!("після {:.0}% попередньої", cleared * 100.0));
+        }
+        (w.entry, trigger.join(" або "))
+    });
+    let hud = format!(
+        "Редактор рівнів — {}, ворогів: {}\nХвиля {} з {} (W), вхід: {} (E) {}\nТип: {} (K), рух: {} (M), вогонь: {} (F), поява через {:.1} с (↑/↓)\nЛКМ — поставити, ПКМ — прибрати, Backspace — скасувати, Delete — очистити\nS — зберегти, L — завантажити, P — перевірити, Esc — вийти\n{}",
+        editor.path,
+        editor.level.enemy_count(),
+        editor.wave + 1,
+        editor.level.waves.len().max(editor.wave + 1),
+        entry.label(),
+        trigger,
+        brush.kind.label(),
+        brush.movement.label(),
+        brush.fire.label(),
+        brush.delay,
+        editor.status
+    );
+    for mut text in text_query.iter_mut() {
+        text.0 = hud.clone();
+    }
+}
+
+pub fn end_preview(mut editor: ResMut<LevelEditor>, mut next_state: ResMut<NextState<GameState>>) {
+    editor.previewing = false;
+    next_state.set(GameState::Editor);
+}
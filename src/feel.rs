@@ -0,0 +1,226 @@
+use bevy::prelude::*;
+use rand::{thread_rng, Rng};
+use crate::camera::MainCamera;
+use crate::game::{GameSet, GameState};
+use crate::ui::NotStartedStruct;
+
+#[derive(Message)]
+pub struct ScreenShake(pub f32);
+
+#[derive(Message)]
+pub struct HitStop(pub f32);
+
+#[derive(Message)]
+pub struct ScreenFlash(pub Color);
+
+pub struct FeelPlugin;
+
+impl Plugin for FeelPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<FeelSettings>()
+            .init_resource::<Trauma>()
+            .init_resource::<HitStopTimer>()
+            .init_resource::<TimeScale>()
+            .add_message::<ScreenShake>()
+            .add_message::<HitStop>()
+            .add_message::<ScreenFlash>()
+            .add_systems(OnEnter(GameState::NotStarted), show_feel_settings)
+            .add_systems(Update, feel_settings_keys.run_if(in_state(GameState::NotStarted)))
+            .add_systems(Update, (
+                (receive_screen_shake, shake_camera).chain(),
+                (receive_hit_stop, update_hit_stop).chain(),
+                (receive_screen_flash, fade_screen_flash).chain(),
+            ).in_set(GameSet::Presentation));
+    }
+}
+
+#[derive(Resource)]
+pub struct FeelSettings {
+    pub screen_shake: bool,
+    pub hit_stop: bool,
+    pub screen_flash: bool,
+}
+
+impl Default for FeelSettings {
+    fn default() -> Self {
+        Self { screen_shake: true, hit_stop: true, screen_flash: true }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Trauma(pub f32);
+
+#[derive(Resource, Default)]
+pub struct HitStopTimer(pub Option<Timer>);
+
+#[derive(Resource)]
+pub struct TimeScale(pub f32);
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+#[derive(Component)]
+pub struct ScreenFlashStruct {
+    pub color: Color,
+    pub timer: Timer,
+}
+
+#[derive(Component)]
+pub struct FeelSettingsText;
+
+pub fn receive_screen_shake(
+    settings: Res<FeelSettings>,
+    mut shakes: MessageReader<ScreenShake>,
+    mut trauma: ResMut<Trauma>,
+) {
+    for ScreenShake(amount) in shakes.read() {
+        if settings.screen_shake {
+            trauma.0 = (trauma.0 + amount).min(1.0);
+        }
+    }
+}
+
+pub fn shake_camera(
+    time: Res<Time<Real>>,
+    mut trauma: ResMut<Trauma>,
+    mut camera: Query<&mut Transform, With<MainCamera>>,
+) {
+    trauma.0 = (trauma.0 - 1.5 * time.delta_secs()).max(0.0);
+    let shake = trauma.0 * trauma.0;
+    let mut rng = thread_rng();
+    for mut transform in camera.iter_mut() {
+        transform.translation.x = 18.0 * shake * rng.gen_range(-1.0..1.0_f32);
+        transform.translation.y = 18.0 * shake * rng.gen_range(-1.0..1.0_f32);
+        transform.rotation = Quat::from_rotation_z(0.03 * shake * rng.gen_range(-1.0..1.0_f32));
+    }
+}
+
+pub fn receive_hit_stop(
+    settings: Res<FeelSettings>,
+    mut hit_stops: MessageReader<HitStop>,
+    mut hit_stop: ResMut<HitStopTimer>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    for HitStop(secs) in hit_stops.read() {
+        if settings.hit_stop {
+            hit_stop.0 = Some(Timer::from_seconds(*secs, TimerMode::Once));
+            time.set_relative_speed(0.0);
+        }
+    }
+}
+
+pub fn update_hit_stop(
+    real: Res<Time<Real>>,
+    scale: Res<TimeScale>,
+    mut hit_stop: ResMut<HitStopTimer>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if let Some(timer) = hit_stop.0.as_mut() && timer.tick(real.delta()).is_finished() {
+        hit_stop.0 = None;
+        time.set_relative_speed(scale.0);
+    }
+}
+
+pub fn receive_screen_flash(
+    settings: Res<FeelSettings>,
+    mut flashes: MessageReader<ScreenFlash>,
+    mut commands: Commands,
+) {
+    for ScreenFlash(color) in flashes.read() {
+        if settings.screen_flash {
+            commands.spawn((
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    ..default()
+                },
+                BackgroundColor(*color),
+                GlobalZIndex(100),
+                ScreenFlashStruct { color: *color, timer: Timer::from_seconds(0.25, TimerMode::Once) },
+            ));
+        }
+    }
+}
+
+pub fn fade_screen_flash(
+    time: Res<Time<Real>>,
+    mut commands: Commands,
+    mut flashes: Query<(Entity, &mut ScreenFlashStruct, &mut BackgroundColor)>,
+) {
+    for (entity, mut flash, mut background) in flashes.iter_mut() {
+        if flash.timer.tick(time.delta()).is_finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let alpha = flash.color.alpha() * flash.timer.fraction_remaining();
+        background.0 = flash.color.with_alpha(alpha);
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value { "увімк." } else { "вимк." }
+}
+
+fn feel_settings_line(settings: &FeelSettings) -> String {
+    format!(
+        "1 — тряска екрану: {}   2 — стоп-кадр: {}   3 — спалахи: {}",
+        on_off(settings.screen_shake),
+        on_off(settings.hit_stop),
+        on_off(settings.screen_flash)
+    )
+}
+
+pub fn show_feel_settings(
+    mut commands: Commands,
+    settings: Res<FeelSettings>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            bottom: Val::Px(40.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        NotStartedStruct,
+        DespawnOnExit(GameState::NotStarted),
+    )).with_children(|p| {
+        p.spawn((
+            Text::new(feel_settings_line(&settings)),
+            TextFont {
+                font: asset_server.load("fonts/e-ukrainehead-bold_w.ttf"),
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            FeelSettingsText,
+        ));
+    });
+}
+
+pub fn feel_settings_keys(
+    key_code: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<FeelSettings>,
+    mut text_query: Query<&mut Text, With<FeelSettingsText>>,
+) {
+    if key_code.just_pressed(KeyCode::Digit1) {
+        settings.screen_shake = !settings.screen_shake;
+    }
+    if key_code.just_pressed(KeyCode::Digit2) {
+        settings.hit_stop = !settings.hit_stop;
+    }
+    if key_code.just_pressed(KeyCode::Digit3) {
+        settings.screen_flash = !settings.screen_flash;
+    }
+    if settings.is_changed() {
+        for mut text in text_query.iter_mut() {
+            text.0 = feel_settings_line(&settings);
+        }
+    }
+}
@@ -1,23 +1,18 @@
 use bevy::prelude::*;
+use bevy::window::WindowFocused;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use crate::animation::AnimationAtlases;
+use crate::console::*;
+use crate::difficulty::Difficulty;
+use crate::feel::*;
 use crate::levels::*;
 use crate::lasers_enemies::LasersEnemiesStruct;
-use crate::lasers_player::LasersPlayerStruct;
-use crate::player::PlayerStruct;
-
-#[derive(Component)]
-pub struct BackgroundStruct;
-
-#[derive(Component)]
-pub struct PauseStruct;
-
-#[derive(Component)]
-pub struct GameOverStruct;
-
-#[derive(Component)]
-pub struct WinStruct;
-
-#[derive(Component)]
-pub struct NotStartedStruct;
+use crate::particles::*;
+use crate::physics::*;
+use crate::player::*;
+use crate::stats::*;
+use crate::waves::WaveDirector;
 
 #[derive(Component)]
 pub struct GameplayObject;
@@ -29,112 +24,227 @@ pub enum GameState {
     InGame,
     GameOver,
     Pause,
-    Win
+    Win,
+    Intermission,
+    NetMenu,
+    Online,
+    Editor,
+    Mods,
+    Achievements,
+}
+
+pub const CONSOLE_STATE_NAMES: &[&str] = &["menu", "game", "pause", "gameover", "win", "net", "editor", "mods", "achievements"];
+
+impl GameState {
+    pub fn from_console_name(name: &str) -> Option<Self> {
+        match name {
+            "menu" => Some(GameState::NotStarted),
+            "game" => Some(GameState::InGame),
+            "pause" => Some(GameState::Pause),
+            "gameover" => Some(GameState::GameOver),
+            "win" => Some(GameState::Win),
+            "net" => Some(GameState::NetMenu),
+            "editor" => Some(GameState::Editor),
+            "mods" => Some(GameState::Mods),
+            "achievements" => Some(GameState::Achievements),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Resource, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameMode {
+    #[default]
+    Single,
+    Coop,
+    Versus,
+    Bot,
+    Agent,
+}
+
+#[derive(Message, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum GameplayEvent {
+    EnemyDestroyed,
+    EnemyLaserIntercepted,
+    PlayerHit,
+    LevelCleared(LevelState),
+}
+
+#[derive(Resource)]
+pub struct GameRng(pub StdRng);
+
+impl GameRng {
+    pub fn seeded(seed: u64) -> Self {
+        Self(StdRng::seed_from_u64(seed))
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct GodMode(pub bool);
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self(StdRng::from_entropy())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct InRun;
+
+impl ComputedStates for InRun {
+    type SourceStates = GameState;
+
+    const ALLOW_SAME_STATE_TRANSITIONS: bool = false;
+
+    fn compute(state: GameState) -> Option<Self> {
+        match state {
+            GameState::InGame | GameState::Pause | GameState::Intermission => Some(InRun),
+            _ => None,
+        }
+    }
+}
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GameSet {
+    Input,
+    Movement,
+    Collision,
+    Resolution,
+    Presentation,
+}
+
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_state::<GameState>()
+            .add_computed_state::<InRun>()
+            .init_resource::<GameMode>()
+            .init_resource::<GameRng>()
+            .init_resource::<GodMode>()
+            .add_message::<WindowFocused>()
+            .add_message::<GameplayEvent>()
+            .configure_sets(Update, GameSet::Input.run_if(in_state(GameState::InGame)))
+            .configure_sets(FixedUpdate, (
+                GameSet::Movement,
+                GameSet::Collision,
+                GameSet::Resolution,
+            ).chain().run_if(in_state(GameState::InGame)))
+            .configure_sets(Update, GameSet::Presentation.after(GameSet::Input))
+            .add_systems(OnEnter(InRun), start_new_run)
+            .add_systems(OnEnter(GameState::Pause), pause_virtual_time)
+            .add_systems(OnExit(GameState::Pause), resume_virtual_time)
+            .add_systems(Update, pause_on_focus_lost.run_if(in_state(GameState::InGame)))
+            .add_systems(Update, start.run_if(in_state(GameState::NotStarted)))
+            .add_systems(Update, keys.before(GameSet::Input))
+            .add_systems(FixedUpdate, game_over
+                .in_set(GameSet::Resolution)
+                .run_if(not(resource_equals(GameMode::Versus))))
+            .add_systems(FixedUpdate, win_game
+                .in_set(GameSet::Resolution)
+                .run_if(in_state(LevelState::Level3)))
+            .add_systems(Update, restart_game
+                .run_if(in_state(GameState::GameOver).or(in_state(GameState::Win))))
+            .add_systems(Update, update_gameplay.in_set(GameSet::Presentation))
+            .add_console_command(ConsoleCommand::new("state", "state <назва> — перейти до стану гри", state_command)
+                .with_arguments(CONSOLE_STATE_NAMES)
+                .cheat())
+            .add_console_command(ConsoleCommand::new("level", "level <1-3> — перейти до рівня", level_command)
+                .with_arguments(&["1", "2", "3"])
+                .cheat())
+            .add_console_command(ConsoleCommand::new("god", "god — увімкнути або вимкнути безсмертя", god_command).cheat())
+            .add_console_command(ConsoleCommand::new("timescale", "timescale <множник> — швидкість гри", timescale_command).cheat())
+            .add_console_command(ConsoleCommand::new("seed", "seed <число> — зерно генератора випадкових чисел", seed_command));
+    }
+}
+
+pub fn state_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let name: String = argument(args, 0, "стан")?;
+    let Some(state) = GameState::from_console_name(&name) else {
+        return Err(format!("невідомий стан {name}, доступні: {}", CONSOLE_STATE_NAMES.join(", ")));
+    };
+    world.resource_mut::<NextState<GameState>>().set(state);
+    Ok(format!("Стан: {state:?}"))
+}
+
+pub fn level_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let level = match argument::<u32>(args, 0, "рівень")? {
+        1 => LevelState::Level1,
+        2 => LevelState::Level2,
+        3 => LevelState::Level3,
+        n => return Err(format!("рівня {n} не існує")),
+    };
+    if *world.resource::<State<GameState>>().get() != GameState::InGame {
+        world.resource_mut::<NextState<GameState>>().set(GameState::InGame);
+    }
+    world.resource_mut::<NextState<LevelState>>().set(level);
+    Ok(format!("Рівень {}", level.number()))
+}
+
+pub fn god_command(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    let mut god = world.resource_mut::<GodMode>();
+    god.0 = !god.0;
+    Ok(if god.0 { "Безсмертя увімкнено" } else { "Безсмертя вимкнено" }.to_string())
+}
+
+pub fn timescale_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let scale: f32 = argument(args, 0, "множник")?;
+    if !scale.is_finite() || scale <= 0.0 {
+        return Err("множник має бути додатним".to_string());
+    }
+    world.resource_mut::<TimeScale>().0 = scale;
+    if world.resource::<HitStopTimer>().0.is_none() {
+        world.resource_mut::<Time<Virtual>>().set_relative_speed(scale);
+    }
+    Ok(format!("Швидкість гри: {scale}"))
+}
+
+pub fn seed_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let seed: u64 = argument(args, 0, "зерно")?;
+    world.insert_resource(GameRng::seeded(seed));
+    Ok(format!("Зерно: {seed}"))
 }
 
 pub fn update_gameplay(
     state: Res<State<GameState>>,
     mut query: Query<&mut Visibility, With<GameplayObject>>
 ) {
-    let v = *state.get() == GameState::InGame;
+    let v = matches!(state.get(), GameState::InGame | GameState::Pause | GameState::Intermission);
     let vy = if v {Visibility::Visible} else { Visibility::Hidden };
     for mut v2 in query.iter_mut() {
         *v2 = vy
     }
 }
 
-pub fn load_background_for_game(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.spawn((
-        Sprite {
-            image: asset_server.load("images/backgrounds/Background_for_game.png"),
-            ..default()
-        },
-        Transform::from_xyz(0.0, 0.0, 0.0),
-        BackgroundStruct
-    ));
-}
-
 pub fn keys(
     mut next_state: ResMut<NextState<GameState>>,
     key_code: Res<ButtonInput<KeyCode>>,
     state: Res<State<GameState>>,
-    mut commands: Commands,
-    pause_query: Query<Entity, With<PauseStruct>>,
-    asset_server: Res<AssetServer>
 ) {
     if key_code.just_pressed(KeyCode::KeyP) {
         if *state.get() == GameState::InGame {
             next_state.set(GameState::Pause);
-            commands.spawn((
-                Node {
-                    position_type: PositionType::Absolute,
-                    width: Val::Percent(100.0),
-                    height: Val::Percent(100.0),
-                    justify_content: JustifyContent::Center,
-                    align_items: AlignItems::Center,
-                    ..default()
-                },
-                PauseStruct
-            )).with_children(|parent| {
-                parent.spawn((
-                    Text::new("Пауза"),
-                    TextFont {
-                        font: asset_server.load("fonts/e-UkraineHead-Medium.otf"),
-                        font_size: 60.0,
-                        ..default()
-                    },
-                    TextColor(Color::WHITE),
-                ));
-            });
-
-            commands.spawn((
-                AudioPlayer::new(asset_server.load("sounds/pause.ogg")),
-                PlaybackSettings::ONCE,
-            ));
         } else if *state.get() == GameState::Pause {
             next_state.set(GameState::InGame);
-            for entity in pause_query.iter() {
-                commands.entity(entity).despawn();
-            }
         }
     }
 }
 
-pub fn show_start_text(
-    mut commands: Commands,
-    state: Res<State<GameState>>,
-    asset_server: Res<AssetServer>,
-) {
-    if *state.get() == GameState::NotStarted {
-        commands.spawn((
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                ..default()
-            },
-            NotStartedStruct,
-        )).with_children(|p| {
-            p.spawn((
-                Text::new("Гра не розпочата, натисніть на S для старту гри"),
-                TextFont {
-                    font: asset_server.load("fonts/e-ukrainehead-bold_w.ttf"),
-                    font_size: 25.0,
-                    ..default()
-                },
-                TextColor(Color::WHITE),
-            ));
-        });
-    }
+pub fn pause_virtual_time(mut time: ResMut<Time<Virtual>>) {
+    time.pause();
 }
 
-pub fn clean_start_text(
-    mut commands: Commands,
-    not_started_query: Query<Entity, With<NotStartedStruct>>
+pub fn resume_virtual_time(mut time: ResMut<Time<Virtual>>) {
+    time.unpause();
+}
+
+pub fn pause_on_focus_lost(
+    mut focus: MessageReader<WindowFocused>,
+    mut next_state: ResMut<NextState<GameState>>,
 ) {
-    for e in not_started_query.iter() {
-        commands.entity(e).despawn();
+    if focus.read().any(|event| !event.focused) {
+        next_state.set(GameState::Pause);
     }
 }
 
@@ -142,158 +252,124 @@ pub fn start(
     key_code: Res<ButtonInput<KeyCode>>,
     state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
-    mut next_level: ResMut<NextState<LevelState>>
+    mut mode: ResMut<GameMode>,
 ) {
-    if key_code.just_pressed(KeyCode::KeyS) && *state.get() == GameState::NotStarted {
-        next_level.set(LevelState::Level1);
+    if *state.get() != GameState::NotStarted {
+        return;
+    }
+    if key_code.just_pressed(KeyCode::KeyS) {
+        *mode = GameMode::Single;
+        next_state.set(GameState::InGame);
+    } else if key_code.just_pressed(KeyCode::KeyC) {
+        *mode = GameMode::Coop;
+        next_state.set(GameState::InGame);
+    } else if key_code.just_pressed(KeyCode::KeyV) {
+        *mode = GameMode::Versus;
         next_state.set(GameState::InGame);
     }
 }
 
+pub fn start_new_run(
+    mut commands: Commands,
+    time: Res<Time>,
+    atlases: Res<AnimationAtlases>,
+    mode: Res<GameMode>,
+    gamepads: Query<Entity, With<Gamepad>>,
+    difficulty: Res<Difficulty>,
+    god: Res<GodMode>,
+    scale: Res<TimeScale>,
+    mut cheats: ResMut<ConsoleCheats>,
+    mut shoot_timer: ResMut<EnemyShootTimer>,
+    mut director: ResMut<WaveDirector>,
+    mut score: ResMut<Score>,
+    mut stats: ResMut<LevelStats>,
+) {
+    cheats.run = std::mem::take(&mut cheats.next_run) || god.0 || scale.0 != 1.0;
+    shoot_timer.0.reset();
+    *director = WaveDirector::default();
+    score.0 = 0;
+    *stats = LevelStats {
+        started_at: time.elapsed_secs(),
+        ..default()
+    };
+    let gamepads: Vec<Entity> = gamepads.iter().collect();
+    spawn_players(&mut commands, &atlases, *mode, *difficulty, &gamepads);
+}
+
 pub fn game_over(
     mut commands: Commands,
-    player_query: Query<(&Transform, Entity), With<PlayerStruct>>,
-    lasers_enemies_query: Query<(&Transform, Entity), With<LasersEnemiesStruct>>,
-    state: Res<State<GameState>>,
+    mut player_query: Query<(&Transform, &Collider, &mut Lives, Entity), ActivePlayer>,
+    all_players: Query<Entity, With<PlayerStruct>>,
+    lasers_enemies_query: Query<(&PhysicalTranslation, &PreviousPhysicalTranslation, Entity), With<LasersEnemiesStruct>>,
     mut next_state: ResMut<NextState<GameState>>,
-    asset_server: Res<AssetServer>,
-    player_lasers_query: Query<Entity, With<LasersPlayerStruct>>,
-    enemies_query_l1: Query<Entity, With<EnemiesStructInLevel1>>,
-    enemies_query_l2: Query<Entity, With<EnemiesStructInLevel2>>,
-    enemies_query_l3: Query<Entity, With<EnemiesStructInLevel3>>,
+    god: Res<GodMode>,
+    mut stats: ResMut<LevelStats>,
+    mut events: MessageWriter<GameplayEvent>,
+    mut shake: MessageWriter<ScreenShake>,
+    mut hit_stop: MessageWriter<HitStop>,
+    mut flash: MessageWriter<ScreenFlash>,
 ) {
-    for (player_tr, player_entity) in player_query.iter() {
-        for (lasers_enemies_tr, lasers_enemies_entity) in lasers_enemies_query.iter() {
-            let collision = lasers_enemies_tr.translation.y > player_tr.translation.y - 20.0
-                && lasers_enemies_tr.translation.y < player_tr.translation.y + 20.0
-                && (lasers_enemies_tr.translation.x - player_tr.translation.x).abs() < 20.0;
-
-            if collision && *state.get() == GameState::InGame {
-                next_state.set(GameState::GameOver);
-                commands.spawn((
-                    Node {
-                        width: Val::Percent(100.0),
-                        height: Val::Percent(100.0),
-                        position_type: PositionType::Absolute,
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        ..default()
-                    },
-                    GameOverStruct,
-                )).with_children(|parent| {
-                    parent.spawn((
-                        Text::new("Гра програна! Натисніть на R для рестарту гри"),
-                        TextFont {
-                            font: asset_server.load("fonts/e-Ukraine-Bold.otf"),
-                            font_size: 25.0,
-                            ..default()
-                        },
-                        TextColor(Color::WHITE)
-                    ));
-                });
-                commands.spawn((
-                    AudioPlayer::new(asset_server.load("sounds/game_over.ogg")),
-                    PlaybackSettings::ONCE,
-                ));
-                commands.entity(player_entity).despawn();
+    let mut standing = player_query.iter().count();
+    let mut used_lasers = Vec::new();
+
+    for (player_tr, collider, mut lives, player_entity) in player_query.iter_mut() {
+        for (current, previous, lasers_enemies_entity) in lasers_enemies_query.iter() {
+            if used_lasers.contains(&lasers_enemies_entity) {
+                continue;
+            }
+            let collision = collider.swept(player_tr.translation.truncate(), previous.0, current.0);
+
+            if collision {
+                used_lasers.push(lasers_enemies_entity);
                 commands.entity(lasers_enemies_entity).despawn();
-                for e in player_lasers_query.iter() {
-                    commands.entity(e).despawn();
+                if god.0 {
+                    break;
                 }
-                for e_l1 in enemies_query_l1.iter() {
-                    for e_l2 in enemies_query_l2.iter() {
-                        for e_l3 in enemies_query_l3.iter() {
-                            commands.entity(e_l1).despawn();
-                            commands.entity(e_l2).despawn();
-                            commands.entity(e_l3).despawn();
-                        }
-                    }
+                lives.0 = lives.0.saturating_sub(1);
+                stats.lives_lost += 1;
+                events.write(GameplayEvent::PlayerHit);
+                shake.write(ScreenShake(0.8));
+                flash.write(ScreenFlash(Color::srgba(1.0, 0.1, 0.1, 0.5)));
+                if lives.0 == 0 {
+                    standing -= 1;
+                    spawn_particle_burst(&mut commands, player_tr.translation, ParticleEmitter::explosion());
+                    commands.entity(player_entity).insert(Downed::default());
                 }
-                return;
+                break;
             }
         }
     }
+
+    if standing == 0 && !used_lasers.is_empty() {
+        hit_stop.write(HitStop(0.25));
+        for entity in all_players.iter() {
+            commands.entity(entity).despawn();
+        }
+        next_state.set(GameState::GameOver);
+    }
 }
 
 pub fn restart_game(
     key_code: Res<ButtonInput<KeyCode>>,
     mut next_state: ResMut<NextState<GameState>>,
-    mut next_level: ResMut<NextState<LevelState>>,
-    mut commands: Commands,
-    game_over_query: Query<Entity, With<GameOverStruct>>,
-    win_query: Query<Entity, With<WinStruct>>,
-    enemies_l1: Query<Entity, With<EnemiesStructInLevel1>>,
-    enemies_l2: Query<Entity, With<EnemiesStructInLevel2>>,
-    enemies_l3: Query<Entity, With<EnemiesStructInLevel3>>,
-    lasers_enemies_query: Query<Entity, With<LasersEnemiesStruct>>,
-    lasers_player_query: Query<Entity, With<LasersPlayerStruct>>,
-    asset_server: Res<AssetServer>,
-    mut shoot_timer: ResMut<EnemyShootTimer>,
-    mut spawned: ResMut<LevelEnemiesSpawned>,
-    state: Res<State<GameState>>
 ) {
     if key_code.just_pressed(KeyCode::KeyR) {
-        for e in game_over_query.iter() { commands.entity(e).despawn(); }
-        for e in win_query.iter() { commands.entity(e).despawn(); }
-        for e in enemies_l1.iter() { commands.entity(e).try_despawn(); }
-        for e in enemies_l2.iter() { commands.entity(e).try_despawn(); }
-        for e in enemies_l3.iter() { commands.entity(e).try_despawn(); }
-        for e in lasers_enemies_query.iter() { commands.entity(e).try_despawn(); }
-        for e in lasers_player_query.iter() { commands.entity(e).try_despawn(); }
-
-        shoot_timer.0.reset();
-        spawned.0 = false;
-
-        if *state.get() == GameState::GameOver {
-            commands.spawn((
-                Sprite {
-                    image: asset_server.load("images/players/player.png"),
-                    ..default()
-                },
-                Transform::from_xyz(0.0, -370.0, 0.5),
-                PlayerStruct,
-                GameplayObject
-            ));
-        }
-
-        next_level.set(LevelState::Level1);
         next_state.set(GameState::NotStarted);
     }
 }
 
 pub fn win_game(
-    mut commands: Commands,
     mut next_state: ResMut<NextState<GameState>>,
-    asset_server: Res<AssetServer>,
     enemies_l3: Query<&EnemiesStructInLevel3>,
-    spawned: Res<LevelEnemiesSpawned>,
+    director: Res<WaveDirector>,
+    mut events: MessageWriter<GameplayEvent>,
+    mut hit_stop: MessageWriter<HitStop>,
+    mut flash: MessageWriter<ScreenFlash>,
 ) {
-    if spawned.0 && enemies_l3.is_empty() {
+    if director.is_cleared(enemies_l3.iter().len()) {
         next_state.set(GameState::Win);
-        commands.spawn((
-            Node {
-                width: Val::Percent(100.0),
-                height: Val::Percent(100.0),
-                position_type: PositionType::Absolute,
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                ..default()
-            },
-            WinStruct,
-        )).with_children(|p| {
-            p.spawn((
-                Text::new("Ви виграли гру! Натисніть R для рестарту"),
-                TextFont {
-                    font: asset_server.load("fonts/e-UkraineHead-Bold.otf"),
-                    font_size: 25.0,
-                    ..default()
-                },
-                TextColor(Color::WHITE),
-            ));
-        });
-        commands.spawn((
-            AudioPlayer::new(asset_server.load("sounds/win.ogg")),
-            PlaybackSettings::ONCE,
-        ));
+        events.write(GameplayEvent::LevelCleared(LevelState::Level3));
+        hit_stop.write(HitStop(0.3));
+        flash.write(ScreenFlash(Color::srgba(1.0, 1.0, 1.0, 0.6)));
     }
 }
@@ -0,0 +1,91 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use crate::game::{GameMode, GameRng, GameState};
+use crate::levels::LevelState;
+use crate::mods::ModSourcePlugin;
+use crate::physics::DEFAULT_TICK_RATE;
+use crate::SpaceShooterPlugins;
+
+pub const HEADLESS_STEP: f32 = 1.0 / 60.0;
+
+pub fn headless_app() -> App {
+    headless_app_with_mods(ModSourcePlugin::none())
+}
+
+pub fn headless_app_with_mods(mods: ModSourcePlugin) -> App {
+    let mut app = App::new();
+    app
+        .add_plugins((MinimalPlugins, StatesPlugin, mods, AssetPlugin::default()))
+        .init_asset::<Image>()
+        .init_asset::<TextureAtlasLayout>()
+        .init_asset::<Font>()
+        .init_asset::<AudioSource>()
+        .init_resource::<ButtonInput<KeyCode>>()
+        .init_resource::<ButtonInput<MouseButton>>()
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / DEFAULT_TICK_RATE)))
+        .add_plugins(SpaceShooterPlugins);
+    app
+}
+
+pub const LEVEL_COUNT: u32 = 3;
+
+#[derive(Debug, Clone, Copy)]
+pub struct BalanceRun {
+    pub seed: u64,
+    pub reached: u32,
+    pub won: bool,
+    pub seconds: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelBalance {
+    pub level: u32,
+    pub attempts: u32,
+    pub clears: u32,
+}
+
+impl LevelBalance {
+    pub fn win_rate(&self) -> f32 {
+        if self.attempts == 0 {
+            0.0
+        } else {
+            self.clears as f32 / self.attempts as f32
+        }
+    }
+}
+
+pub fn balance_run(seed: u64, limit_secs: f32) -> BalanceRun {
+    let mut app = headless_app();
+    app.insert_resource(GameRng::seeded(seed));
+    app.insert_resource(GameMode::Bot);
+    app.update();
+    app.world_mut().resource_mut::<NextState<GameState>>().set(GameState::InGame);
+
+    let mut reached = 1;
+    let mut seconds = 0.0;
+    while seconds < limit_secs {
+        app.update();
+        seconds += HEADLESS_STEP;
+        if let Some(level) = app.world().get_resource::<State<LevelState>>() {
+            reached = reached.max(level.get().number());
+        }
+        match app.world().resource::<State<GameState>>().get() {
+            GameState::Win => return BalanceRun { seed, reached, won: true, seconds },
+            GameState::GameOver => break,
+            _ => {}
+        }
+    }
+    BalanceRun { seed, reached, won: false, seconds }
+}
+
+pub fn balance_report(runs: &[BalanceRun]) -> Vec<LevelBalance> {
+    (1..=LEVEL_COUNT).map(|level| {
+        let attempts = runs.iter().filter(|r| r.reached >= level).count() as u32;
+        let clears = runs.iter()
+            .filter(|r| r.reached > level || (r.reached == level && r.won))
+            .count() as u32;
+        LevelBalance { level, attempts, clears }
+    }).collect()
+}
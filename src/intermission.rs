@@ -0,0 +1,148 @@
+use bevy::prelude::*;
+use crate::difficulty::Difficulty;
+use crate::game::{GameMode, GameState};
+use crate::lasers_enemies::LasersEnemiesStruct;
+use crate::lasers_player::LasersPlayerStruct;
+use crate::levels::LevelState;
+use crate::player::{PlayerId, PlayerStruct};
+use crate::stats::*;
+
+#[derive(Component)]
+pub struct IntermissionStruct;
+
+pub struct IntermissionPlugin;
+
+impl Plugin for IntermissionPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(GameState::Intermission), show_intermission)
+            .add_systems(OnExit(GameState::Intermission), clean_intermission)
+            .add_systems(Update, update_intermission.run_if(in_state(GameState::Intermission)));
+    }
+}
+
+#[derive(Component)]
+pub struct IntermissionText;
+
+#[derive(Resource)]
+pub struct Intermission {
+    pub completed: LevelState,
+    pub next: LevelState,
+    pub summary: Timer,
+    pub countdown: Timer,
+}
+
+impl Intermission {
+    pub fn new(completed: LevelState, next: LevelState) -> Self {
+        Self {
+            completed,
+            next,
+            summary: Timer::from_seconds(3.0, TimerMode::Once),
+            countdown: Timer::from_seconds(3.0, TimerMode::Once),
+        }
+    }
+}
+
+pub fn begin_intermission(
+    commands: &mut Commands,
+    next_state: &mut NextState<GameState>,
+    completed: LevelState,
+    next: LevelState,
+) {
+    commands.insert_resource(Intermission::new(completed, next));
+    next_state.set(GameState::Intermission);
+}
+
+pub fn show_intermission(
+    mut commands: Commands,
+    time: Res<Time>,
+    intermission: Res<Intermission>,
+    stats: Res<LevelStats>,
+    mut score: ResMut<Score>,
+    asset_server: Res<AssetServer>,
+    mode: Res<GameMode>,
+    difficulty: Res<Difficulty>,
+    players: Query<(&PlayerId, &PlayerScore, &Lives), With<PlayerStruct>>,
+    lasers_player: Query<Entity, With<LasersPlayerStruct>>,
+    lasers_enemies: Query<Entity, With<LasersEnemiesStruct>>,
+) {
+    for e in lasers_player.iter().chain(lasers_enemies.iter()) {
+        commands.entity(e).despawn();
+    }
+
+    let time_taken = time.elapsed_secs() - stats.started_at;
+    let bonus = difficulty.points(stats.bonus(time_taken));
+    score.0 += bonus;
+
+    let mut summary = format!(
+        "Рівень {} пройдено!\n\nТочність: {:.0}% ({}/{})\nЗнищено ворогів: {}\nЧас: {:.1} с\nБонус: +{}\nРахунок: {}",
+        intermission.completed.number(),
+        stats.accuracy() * 100.0,
+        stats.hits,
+        stats.shots_fired,
+        stats.enemies_destroyed,
+        time_taken,
+        bonus,
+        score.0
+    );
+
+    if *mode == GameMode::Coop {
+        let mut players: Vec<_> = players.iter().collect();
+        players.sort_by_key(|(id, _, _)| id.0);
+        for (id, player_score, lives) in players {
+            summary += &format!("\nГравець {}: {} (життів: {})", id.0 + 1, player_score.0, lives.0);
+        }
+    }
+
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        IntermissionStruct,
+        DespawnOnExit(GameState::Intermission)
+    )).with_children(|parent| {
+        parent.spawn((
+            Text::new(summary),
+            TextFont {
+                font: asset_server.load("fonts/e-UkraineHead-Medium.otf"),
+                font_size: 30.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+            IntermissionText
+        ));
+    });
+}
+
+pub fn update_intermission(
+    time: Res<Time>,
+    mut intermission: ResMut<Intermission>,
+    mut text_query: Query<&mut Text, With<IntermissionText>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut next_level: ResMut<NextState<LevelState>>,
+) {
+    if !intermission.summary.tick(time.delta()).is_finished() {
+        return;
+    }
+
+    if intermission.countdown.tick(time.delta()).is_finished() {
+        next_level.set(intermission.next);
+        next_state.set(GameState::InGame);
+        return;
+    }
+
+    let remaining = intermission.countdown.remaining_secs().ceil() as u32;
+    for mut text in text_query.iter_mut() {
+        text.0 = format!("Рівень {} — приготуйтесь!\n\n{}", intermission.next.number(), remaining);
+    }
+}
+
+pub fn clean_intermission(mut commands: Commands) {
+    commands.remove_resource::<Intermission>();
+}
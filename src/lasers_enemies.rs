@@ -1,37 +1,46 @@
 use bevy::prelude::*;
-use crate::game::GameplayObject;
+use crate::difficulty::Challenge;
+use crate::feel::ScreenShake;
+use crate::game::{GameSet, GameplayEvent, GameplayObject, InRun};
 use crate::levels::*;
 use crate::lasers_player::LasersPlayerStruct;
+use crate::particles::*;
+use crate::physics::*;
+use crate::tuning::GameTuning;
+use crate::waves::EnemyEntry;
 
 #[derive(Component)]
 pub struct LasersEnemiesStruct;
 
+#[derive(Component)]
+pub struct LaserVelocity(pub Vec2);
+
+pub struct LasersEnemiesPlugin;
+
+impl Plugin for LasersEnemiesPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(FixedUpdate, (enemies_shoot, move_lasers_enemies).chain().in_set(GameSet::Movement))
+            .add_systems(FixedUpdate, collision_lasers_player_with_lasers_enemies.in_set(GameSet::Collision));
+    }
+}
+
 pub fn enemies_shoot(
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut timer: ResMut<EnemyShootTimer>,
-    mut enemies_l1_query: Query<&Transform, With<EnemiesStructInLevel1>>,
-    mut enemies_l2_query: Query<&Transform, With<EnemiesStructInLevel2>>,
-    mut enemies_l3_query: Query<&Transform, With<EnemiesStructInLevel3>>,
+    mut volley: Local<u32>,
+    enemies_query: Query<(&Transform, Option<&EnemyFire>, Has<EnemyEntry>), AnyEnemy>,
     time: Res<Time>
 ) {
     if timer.0.tick(time.delta()).just_finished() {
+        *volley += 1;
         let mut shoot = false;
-        for enemy_transform in enemies_l1_query.iter_mut().chain(enemies_l2_query.iter_mut())
-            .chain(enemies_l3_query.iter_mut()){
-            commands.spawn((
-                Sprite {
-                    image: asset_server.load("images/lasers/laserRed.png"),
-                    ..default()
-                },
-                Transform::from_xyz(
-                    enemy_transform.translation.x,
-                    enemy_transform.translation.y,
-                    0.5
-                ),
-                LasersEnemiesStruct,
-                GameplayObject
-            ));
+        for (enemy_transform, fire, entering) in enemies_query.iter() {
+            if entering || !fire.copied().unwrap_or_default().fires_on(*volley) {
+                continue;
+            }
+            spawn_enemy_laser(&mut commands, &asset_server, enemy_transform.translation.truncate());
             shoot = true;
         }
         if shoot {
@@ -43,36 +52,61 @@ pub fn enemies_shoot(
     }
 }
 
+pub fn spawn_enemy_laser(commands: &mut Commands, asset_server: &AssetServer, position: Vec2) -> Entity {
+    commands.spawn((
+        Sprite {
+            image: asset_server.load("images/lasers/laserRed.png"),
+            ..default()
+        },
+        Transform::from_translation(position.extend(0.5)),
+        physical_translation(position),
+        Collider::new(Vec2::splat(10.0)),
+        LasersEnemiesStruct,
+        GameplayObject,
+        DespawnOnExit(InRun)
+    )).id()
+}
+
 pub fn collision_lasers_player_with_lasers_enemies(
-    lasers_player_query: Query<(&Transform, Entity), With<LasersPlayerStruct>>,
-    lasers_enemies_query: Query<(&Transform, Entity), With<LasersEnemiesStruct>>,
+    lasers_player_query: Query<(&PhysicalTranslation, &PreviousPhysicalTranslation, Entity), With<LasersPlayerStruct>>,
+    lasers_enemies_query: Query<(&PhysicalTranslation, &PreviousPhysicalTranslation, &Collider, Entity), With<LasersEnemiesStruct>>,
     mut commands: Commands,
-    asset_server: Res<AssetServer>
+    asset_server: Res<AssetServer>,
+    mut shake: MessageWriter<ScreenShake>,
+    mut events: MessageWriter<GameplayEvent>,
 ) {
-    for (lasers_player_t, lasers_player_e) in lasers_player_query.iter() {
-        for (lasers_enemies_t, lasers_enemies_e) in lasers_enemies_query.iter() {
-            let collision = lasers_player_t.translation.y > lasers_enemies_t.translation.y - 10.0
-            && lasers_player_t.translation.y < lasers_enemies_t.translation.y + 10.0
-            && (lasers_player_t.translation.x - lasers_enemies_t.translation.x).abs() < 10.0;
+    for (player_current, player_previous, lasers_player_e) in lasers_player_query.iter() {
+        for (enemies_current, enemies_previous, collider, lasers_enemies_e) in lasers_enemies_query.iter() {
+            let collision = collider.swept(
+                Vec2::ZERO,
+                player_previous.0 - enemies_previous.0,
+                player_current.0 - enemies_current.0
+            );
 
             if collision {
                 commands.entity(lasers_player_e).try_despawn();
                 commands.entity(lasers_enemies_e).try_despawn();
+                spawn_particle_burst(&mut commands, player_current.0.extend(0.5), ParticleEmitter::sparks());
+                shake.write(ScreenShake(0.1));
+                events.write(GameplayEvent::EnemyLaserIntercepted);
                 commands.spawn((
                     AudioPlayer::new(
                         asset_server.load("sounds/collision_of_the_player's_laser_with_the_enemy's_laser.ogg")
                     ),
                     PlaybackSettings::ONCE
                 ));
+                break;
             }
         }
     }
 }
 
 pub fn move_lasers_enemies(mut lasers_enemies_query:
-                           Query<&mut Transform, With<LasersEnemiesStruct>>,
-time: Res<Time>) {
-    for mut t in lasers_enemies_query.iter_mut() {
-        t.translation.y -= 365.0 * time.delta_secs();
+                           Query<(&mut PhysicalTranslation, &mut PreviousPhysicalTranslation, Option<&LaserVelocity>), With<LasersEnemiesStruct>>,
+time: Res<Time>, tuning: Res<GameTuning>, challenge: Challenge) {
+    let speed = challenge.modifiers().laser_speed(&tuning);
+    for (mut current, mut previous, velocity) in lasers_enemies_query.iter_mut() {
+        previous.0 = current.0;
+        current.0 += velocity.map_or(Vec2::new(0.0, -speed), |v| v.0) * time.delta_secs();
     }
 }
@@ -1,27 +1,80 @@
 use bevy::prelude::*;
-use crate::game::GameplayObject;
+use crate::animation::*;
+use crate::difficulty::Difficulty;
+use crate::feel::ScreenShake;
+use crate::game::{GameSet, GameplayEvent, GameplayObject, InRun};
+use crate::lasers_enemies::LasersEnemiesStruct;
 use crate::levels::*;
+use crate::particles::*;
+use crate::physics::*;
 use crate::player::*;
+use crate::stats::*;
+use crate::tuning::GameTuning;
 
 #[derive(Component)]
 pub struct LasersPlayerStruct;
 
+pub struct LasersPlayerPlugin;
+
+impl Plugin for LasersPlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(FixedUpdate, move_lasers.in_set(GameSet::Movement))
+            .add_systems(FixedUpdate, lasers_player
+                .in_set(GameSet::Movement)
+                .after(borders_for_player)
+                .before(clear_player_input))
+            .add_systems(FixedUpdate, collision_lasers_player_with_enemies.in_set(GameSet::Collision));
+    }
+}
+
 pub fn lasers_player(asset_server: Res<AssetServer>, mut commands: Commands,
-mut player_query: Query<&Transform, With<PlayerStruct>>, key_code: Res<ButtonInput<KeyCode>>) {
-    if key_code.just_pressed(KeyCode::Space) {
-        for player_transform in player_query.iter_mut() {
-            commands.spawn((
-                Sprite {
-                    image: asset_server.load("images/lasers/laserGreen.png"),
-                    ..default()
-                },
-                Transform::from_xyz(player_transform.translation.x,
-                                    player_transform.translation.y,
-                                    0.5),
-                LasersPlayerStruct,
-                GameplayObject
-            ));
+player_query: Query<(&Transform, &PlayerInput, &PlayerId, &PlayerSide), ActivePlayer>,
+mut stats: ResMut<LevelStats>) {
+    let mut fired = false;
+    for (player_transform, input, id, side) in player_query.iter() {
+        if !input.fire {
+            continue;
+        }
+        fired = true;
+        let transform = Transform::from_xyz(player_transform.translation.x,
+                                            player_transform.translation.y,
+                                            0.5);
+        let position = physical_translation(player_transform.translation.truncate());
+        match side {
+            PlayerSide::Bottom => {
+                stats.shots_fired += 1;
+                commands.spawn((
+                    Sprite {
+                        image: asset_server.load("images/lasers/laserGreen.png"),
+                        ..default()
+                    },
+                    transform,
+                    position,
+                    LasersPlayerStruct,
+                    *id,
+                    GameplayObject,
+                    DespawnOnExit(InRun)
+                ));
+            }
+            PlayerSide::Top => {
+                commands.spawn((
+                    Sprite {
+                        image: asset_server.load("images/lasers/laserRed.png"),
+                        ..default()
+                    },
+                    transform,
+                    position,
+                    Collider::new(Vec2::splat(10.0)),
+                    LasersEnemiesStruct,
+                    *id,
+                    GameplayObject,
+                    DespawnOnExit(InRun)
+                ));
+            }
         }
+    }
+    if fired {
         commands.spawn((
             AudioPlayer::new(asset_server.load("sounds/player_laser_music.ogg")),
             PlaybackSettings::ONCE,
@@ -31,34 +84,63 @@ mut player_query: Query<&Transform, With<PlayerStruct>>, key_code: Res<ButtonInp
 
 pub fn move_lasers(
     time: Res<Time>,
-    mut lasers_query: Query<&mut Transform, With<LasersPlayerStruct>>,
+    tuning: Res<GameTuning>,
+    mut lasers_query: Query<(&mut PhysicalTranslation, &mut PreviousPhysicalTranslation), With<LasersPlayerStruct>>,
 ) {
-    for mut transform_lasers in lasers_query.iter_mut() {
-        transform_lasers.translation.y += 450.0 * time.delta_secs();
+    for (mut current, mut previous) in lasers_query.iter_mut() {
+        previous.0 = current.0;
+        current.0.y += tuning.player_laser_speed * time.delta_secs();
     }
 }
 
 pub fn collision_lasers_player_with_enemies (
-    mut lasers_query: Query<(&Transform, Entity), With<LasersPlayerStruct>>,
-    mut enemies_l1: Query<(&Transform, Entity), With<EnemiesStructInLevel1>>,
-    mut enemies_l2: Query<(&Transform, Entity), With<EnemiesStructInLevel2>>,
-    mut enemies_l3: Query<(&Transform, Entity), With<EnemiesStructInLevel3>>,
+    lasers_query: Query<(&PhysicalTranslation, &PreviousPhysicalTranslation, &PlayerId, Entity), With<LasersPlayerStruct>>,
+    mut player_scores: Query<(&PlayerId, &mut PlayerScore), With<PlayerStruct>>,
+    mut enemies: Query<(&Transform, &Collider, Option<&mut EnemyHealth>, Entity), AnyEnemy>,
     mut commands: Commands,
-    asset_server: Res<AssetServer>
+    asset_server: Res<AssetServer>,
+    atlases: Res<AnimationAtlases>,
+    mut shake: MessageWriter<ScreenShake>,
+    mut stats: ResMut<LevelStats>,
+    mut score: ResMut<Score>,
+    difficulty: Res<Difficulty>,
+    mut events: MessageWriter<GameplayEvent>,
 ) {
-    for (lasers_tr, lasers_entity) in lasers_query.iter_mut() {
-        let enemies_all_levels = enemies_l1.iter_mut()
-            .chain(enemies_l2.iter_mut())
-            .chain(enemies_l3.iter_mut());
-
-        for (enemies, enemies_entity) in enemies_all_levels {
-            let collision = lasers_tr.translation.y > enemies.translation.y
-                && lasers_tr.translation.y - 30.0 < enemies.translation.y
-                && (lasers_tr.translation.x - enemies.translation.x).abs() < 30.0;
+    let points = difficulty.points(ENEMY_POINTS);
+    for (lasers_current, lasers_previous, owner, lasers_entity) in lasers_query.iter() {
+        for (enemies, collider, health, enemies_entity) in enemies.iter_mut() {
+            let collision = collider.swept(enemies.translation.truncate(), lasers_previous.0, lasers_current.0);
 
+            if collision && let Some(mut health) = health && health.0 > 1 {
+                health.0 -= 1;
+                commands.entity(lasers_entity).try_despawn();
+                stats.hits += 1;
+                spawn_particle_burst(&mut commands, lasers_current.0.extend(0.5), ParticleEmitter::sparks());
+                break;
+            }
             if collision {
                 commands.entity(enemies_entity).try_despawn();
                 commands.entity(lasers_entity).try_despawn();
+                stats.hits += 1;
+                stats.enemies_destroyed += 1;
+                events.write(GameplayEvent::EnemyDestroyed);
+                score.0 += points;
+                for (id, mut player_score) in player_scores.iter_mut() {
+                    if id == owner {
+                        player_score.0 += points;
+                    }
+                }
+                commands.spawn((
+                    atlases.explosion_sprite(),
+                    explosion_animation(),
+                    DespawnOnAnimationEnd,
+                    Transform::from_xyz(enemies.translation.x, enemies.translation.y, 0.6),
+                    GameplayObject,
+                    DespawnOnExit(InRun)
+                ));
+                spawn_particle_burst(&mut commands, lasers_current.0.extend(0.5), ParticleEmitter::laser_impact());
+                spawn_particle_burst(&mut commands, enemies.translation, ParticleEmitter::explosion());
+                shake.write(ScreenShake(0.3));
                 commands.spawn((
                     AudioPlayer::new(
                         asset_server.load("sounds/collision_of_the_player's_laser_with_the_enemy.ogg")
@@ -0,0 +1,142 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use bevy::asset::io::file::FileAssetReader;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+use crate::levels::*;
+use crate::waves::Wave;
+
+pub const LEVEL_FILE_EXTENSION: &str = "level.ron";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct EnemySpawn {
+    pub x: f32,
+    pub y: f32,
+    #[serde(default)]
+    pub kind: EnemyKind,
+    #[serde(default)]
+    pub movement: EnemyMovement,
+    #[serde(default)]
+    pub fire: EnemyFire,
+    #[serde(default)]
+    pub delay: f32,
+    #[serde(default)]
+    pub script: Option<String>,
+}
+
+impl EnemySpawn {
+    pub fn at(x: f32, y: f32) -> Self {
+        Self {
+            x,
+            y,
+            kind: EnemyKind::default(),
+            movement: EnemyMovement::default(),
+            fire: EnemyFire::default(),
+            delay: 0.0,
+            script: None,
+        }
+    }
+}
+
+#[derive(Asset, TypePath, Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct LevelFile {
+    #[serde(default)]
+    pub script: Option<String>,
+    pub waves: Vec<Wave>,
+}
+
+#[derive(Debug)]
+pub enum LevelFileError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    Serialize(ron::Error),
+}
+
+impl fmt::Display for LevelFileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LevelFileError::Io(error) => write!(f, "не вдалося прочитати файл: {error}"),
+            LevelFileError::Parse(error) => write!(f, "помилка у файлі рівня: {error}"),
+            LevelFileError::Serialize(error) => write!(f, "не вдалося записати рівень: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for LevelFileError {}
+
+impl From<std::io::Error> for LevelFileError {
+    fn from(error: std::io::Error) -> Self {
+        LevelFileError::Io(error)
+    }
+}
+
+impl LevelFile {
+    pub fn enemy_count(&self) -> usize {
+        self.waves.iter().map(|w| w.enemies.len()).sum()
+    }
+
+    pub fn from_ron(bytes: &[u8]) -> Result<Self, LevelFileError> {
+        ron::de::from_bytes(bytes).map_err(LevelFileError::Parse)
+    }
+
+    pub fn to_ron(&self) -> Result<String, LevelFileError> {
+        ron::ser::to_string_pretty(self, PrettyConfig::default()).map_err(LevelFileError::Serialize)
+    }
+
+    pub fn read(path: &Path) -> Result<Self, LevelFileError> {
+        Self::from_ron(&std::fs::read(path)?)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), LevelFileError> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_ron()?)?;
+        Ok(())
+    }
+}
+
+pub fn asset_file_path(path: &str) -> PathBuf {
+    FileAssetReader::get_base_path().join("assets").join(path)
+}
+
+#[derive(Default, TypePath)]
+pub struct LevelFileLoader;
+
+impl AssetLoader for LevelFileLoader {
+    type Asset = LevelFile;
+    type Settings = ();
+    type Error = LevelFileError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<LevelFile, LevelFileError> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        LevelFile::from_ron(&bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[LEVEL_FILE_EXTENSION]
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct LevelFiles {
+    pub levels: Vec<Handle<LevelFile>>,
+    pub preview: Option<Handle<LevelFile>>,
+}
+
+impl LevelFiles {
+    pub fn get<'a>(&self, level: LevelState, assets: &'a Assets<LevelFile>) -> Option<&'a LevelFile> {
+        let handle = self.preview.as_ref()
+            .or_else(|| self.levels.get(level.number() as usize - 1))?;
+        assets.get(handle)
+    }
+}
@@ -1,6 +1,24 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
-use rand::{thread_rng, Rng};
-use crate::game::GameplayObject;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::animation::*;
+use crate::console::*;
+use crate::difficulty::{Challenge, DifficultyModifiers};
+use crate::game::{GameMode, GameRng, GameSet, GameState, GameplayEvent, GameplayObject, InRun};
+use crate::intermission::begin_intermission;
+use crate::level_file::*;
+use crate::physics::Collider;
+use crate::scripting::{ScriptedEnemy, apply_script_requests};
+use crate::tuning::GameTuning;
+use crate::waves::*;
+
+pub const ARMORED_TINT: Color = Color::srgb(0.65, 0.75, 1.0);
+pub const SWAY_AMPLITUDE: f32 = 60.0;
+pub const DESCEND_SPEED: f32 = 12.0;
+pub const DESCEND_FLOOR: f32 = -150.0;
 
 #[derive(Resource)]
 pub struct EnemyShootTimer(pub Timer);
@@ -14,10 +32,56 @@ pub struct EnemiesStructInLevel2;
 #[derive(Component)]
 pub struct EnemiesStructInLevel3;
 
+pub type AnyEnemy = Or<(With<EnemiesStructInLevel1>, With<EnemiesStructInLevel2>, With<EnemiesStructInLevel3>)>;
+
+#[derive(Component, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum EnemyKind {
+    #[default]
+    Fighter,
+    Armored,
+}
+
+#[derive(Component, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EnemyMovement {
+    #[default]
+    Static,
+    Sway,
+    Descend,
+}
+
+#[derive(Component, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EnemyFire {
+    #[default]
+    Volley,
+    Alternate,
+    Silent,
+}
+
+#[derive(Component)]
+pub struct EnemyHealth(pub u32);
+
+#[derive(Component)]
+pub struct EnemyOrigin(pub Vec2);
+
+#[derive(Serialize, Deserialize, Default, Clone, Copy, PartialEq, Debug)]
+pub struct EnemyDefinition {
+    #[serde(default)]
+    pub health: Option<u32>,
+    #[serde(default)]
+    pub tint: Option<(f32, f32, f32)>,
+}
+
 #[derive(Resource, Default)]
-pub struct LevelEnemiesSpawned(pub bool);
+pub struct EnemyDefinitions(pub HashMap<EnemyKind, EnemyDefinition>);
+
+#[derive(SystemParam)]
+pub struct LevelLayout<'w> {
+    files: Res<'w, LevelFiles>,
+    assets: Res<'w, Assets<LevelFile>>,
+}
 
-#[derive(Default, States, Eq, PartialEq, Clone, Copy, Debug, Hash)]
+#[derive(Default, SubStates, Eq, PartialEq, Clone, Copy, Debug, Hash)]
+#[source(InRun = InRun)]
 pub enum LevelState {
     #[default]
     Level1,
@@ -25,113 +89,313 @@ pub enum LevelState {
     Level3,
 }
 
-pub fn load_level_1(mut commands: Commands, asset_server: Res<AssetServer>,
-mut spawned: ResMut<LevelEnemiesSpawned>) {
-    let image = asset_server.load("images/players/enemyShip.png");
-    for _ in 0..7 {
-        let x = thread_rng().gen_range(-380.0..380.0_f32);
-        let y = thread_rng().gen_range(100.0..250.0_f32);
-        commands.spawn((
-            Sprite::from_image(image.clone()),
-            Transform::from_xyz(x, y, 0.5),
-            EnemiesStructInLevel1,
-            GameplayObject
-        ));
+pub struct LevelsPlugin;
+
+impl Plugin for LevelsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_sub_state::<LevelState>()
+            .insert_resource(EnemyShootTimer(Timer::from_seconds(GameTuning::default().enemy_shoot_interval, TimerMode::Repeating)))
+            .init_resource::<LevelFiles>()
+            .init_resource::<EnemyDefinitions>()
+            .init_asset::<LevelFile>()
+            .init_asset_loader::<LevelFileLoader>()
+            .add_systems(OnEnter(LevelState::Level1), load_level_1.run_if(not(resource_equals(GameMode::Versus))))
+            .add_systems(OnEnter(LevelState::Level2), load_level_2.run_if(not(resource_equals(GameMode::Versus))))
+            .add_systems(OnEnter(LevelState::Level3), load_level_3.run_if(not(resource_equals(GameMode::Versus))))
+            .add_systems(FixedUpdate, move_patterned_enemies.in_set(GameSet::Movement))
+            .add_systems(FixedUpdate, apply_enemy_definitions
+                .in_set(GameSet::Movement)
+                .after(apply_script_requests))
+            .add_systems(FixedUpdate, distance_between_enemies_in_level_1
+                .in_set(GameSet::Movement)
+                .run_if(in_state(LevelState::Level1)))
+            .add_systems(FixedUpdate, distance_between_enemies_in_level_2
+                .in_set(GameSet::Movement)
+                .run_if(in_state(LevelState::Level2)))
+            .add_systems(FixedUpdate, distance_between_enemies_in_level_3
+                .in_set(GameSet::Movement)
+                .run_if(in_state(LevelState::Level3)))
+            .add_systems(FixedUpdate, check_level_1_complete
+                .in_set(GameSet::Resolution)
+                .run_if(in_state(LevelState::Level1)))
+            .add_systems(FixedUpdate, check_level_2_complete
+                .in_set(GameSet::Resolution)
+                .run_if(in_state(LevelState::Level2)))
+            .add_console_command(ConsoleCommand::new("spawn", "spawn <enemy|armored> <x> <y> [скрипт] — створити ворога", spawn_command)
+                .with_arguments(&["enemy", "armored"])
+                .cheat())
+            .add_console_command(ConsoleCommand::new("kill_all", "kill_all — знищити всіх ворогів", kill_all_command).cheat());
     }
-    spawned.0 = true;
 }
 
-pub fn unload_level_1(
-    mut commands: Commands,
-    enemies: Query<Entity, With<EnemiesStructInLevel1>>,
-    mut spawned: ResMut<LevelEnemiesSpawned>,
-) {
-    for e in enemies.iter() {
-        commands.entity(e).despawn();
+pub fn spawn_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let kind = match args.first().copied() {
+        Some("enemy" | "fighter") => EnemyKind::Fighter,
+        Some("armored") => EnemyKind::Armored,
+        Some(other) => return Err(format!("невідомий тип ворога {other}")),
+        None => return Err("не вказано тип ворога".to_string()),
+    };
+    let spawn = EnemySpawn {
+        x: argument(args, 1, "x")?,
+        y: argument(args, 2, "y")?,
+        kind,
+        movement: EnemyMovement::Static,
+        fire: EnemyFire::Volley,
+        delay: 0.0,
+        script: args.get(3).map(|script| script.to_string()),
+    };
+    let Some(level) = world.get_resource::<State<LevelState>>().map(|level| *level.get()) else {
+        return Err("гра не запущена".to_string());
+    };
+    world.resource_scope(|world, atlases: Mut<AnimationAtlases>| {
+        spawn_enemy(&mut world.commands(), &atlases, level, &spawn, 0);
+        world.flush();
+    });
+    Ok(format!("Створено {} у ({}, {})", kind.label(), spawn.x, spawn.y))
+}
+
+pub fn kill_all_command(world: &mut World, _args: &[&str]) -> Result<String, String> {
+    let enemies: Vec<Entity> = world.query_filtered::<Entity, AnyEnemy>().iter(world).collect();
+    for &enemy in &enemies {
+        world.despawn(enemy);
     }
-    spawned.0 = false;
+    Ok(format!("Знищено ворогів: {}", enemies.len()))
 }
 
-pub fn load_level_2(mut commands: Commands, asset_server: Res<AssetServer>,
-                    mut spawned: ResMut<LevelEnemiesSpawned>) {
-    let image = asset_server.load("images/players/enemyShip.png");
-    for _ in 0..15 {
-        let x = thread_rng().gen_range(-380.0..380.0_f32);
-        let y = thread_rng().gen_range(50.0..250.0_f32);
-        commands.spawn((
-            Sprite::from_image(image.clone()),
-            Transform::from_xyz(x, y, 0.5),
-            EnemiesStructInLevel2,
-            GameplayObject
-        ));
+pub fn enemy_collider() -> Collider {
+    Collider::new(Vec2::new(30.0, 15.0)).with_offset(Vec2::new(0.0, 15.0))
+}
+
+impl EnemyKind {
+    pub fn health(&self) -> u32 {
+        match self {
+            EnemyKind::Fighter => 1,
+            EnemyKind::Armored => 2,
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            EnemyKind::Fighter => EnemyKind::Armored,
+            EnemyKind::Armored => EnemyKind::Fighter,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EnemyKind::Fighter => "винищувач",
+            EnemyKind::Armored => "броньований",
+        }
     }
-    spawned.0 = true;
 }
 
-pub fn unload_level_2(
-    mut commands: Commands,
-    enemies: Query<Entity, With<EnemiesStructInLevel2>>,
-    mut spawned: ResMut<LevelEnemiesSpawned>
-) {
-    for e in enemies.iter() {
-        commands.entity(e).despawn();
+impl EnemyMovement {
+    pub fn next(&self) -> Self {
+        match self {
+            EnemyMovement::Static => EnemyMovement::Sway,
+            EnemyMovement::Sway => EnemyMovement::Descend,
+            EnemyMovement::Descend => EnemyMovement::Static,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EnemyMovement::Static => "на місці",
+            EnemyMovement::Sway => "хитання",
+            EnemyMovement::Descend => "спуск",
+        }
     }
-    spawned.0 = false;
 }
 
-pub fn load_level_3(mut commands: Commands, asset_server: Res<AssetServer>,
-                    mut spawned: ResMut<LevelEnemiesSpawned>) {
-    let image = asset_server.load("images/players/enemyShip.png");
-    for _ in 0..25 {
-        let x = thread_rng().gen_range(-380.0..380.0_f32);
-        let y = thread_rng().gen_range(0.0..250.0_f32);
-        commands.spawn((
-            Sprite::from_image(image.clone()),
-            Transform::from_xyz(x, y, 0.5),
-            EnemiesStructInLevel3,
-            GameplayObject
-        ));
+impl EnemyFire {
+    pub fn next(&self) -> Self {
+        match self {
+            EnemyFire::Volley => EnemyFire::Alternate,
+            EnemyFire::Alternate => EnemyFire::Silent,
+            EnemyFire::Silent => EnemyFire::Volley,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EnemyFire::Volley => "кожен залп",
+            EnemyFire::Alternate => "через залп",
+            EnemyFire::Silent => "не стріляє",
+        }
+    }
+
+    pub fn fires_on(&self, volley: u32) -> bool {
+        match self {
+            EnemyFire::Volley => true,
+            EnemyFire::Alternate => volley.is_multiple_of(2),
+            EnemyFire::Silent => false,
+        }
     }
-    spawned.0 = true;
 }
 
-pub fn unload_level_3(
-    mut commands: Commands,
-    enemies: Query<Entity, With<EnemiesStructInLevel3>>,
-    mut spawned: ResMut<LevelEnemiesSpawned>
+impl LevelState {
+    pub const ALL: [LevelState; 3] = [LevelState::Level1, LevelState::Level2, LevelState::Level3];
+
+    pub fn number(&self) -> u32 {
+        match self {
+            LevelState::Level1 => 1,
+            LevelState::Level2 => 2,
+            LevelState::Level3 => 3,
+        }
+    }
+
+    pub fn enemy_heights(&self) -> Range<f32> {
+        match self {
+            LevelState::Level1 => 100.0..250.0,
+            LevelState::Level2 => 50.0..250.0,
+            LevelState::Level3 => 0.0..250.0,
+        }
+    }
+}
+
+pub fn level_waves(
+    level: LevelState,
+    authored: Option<Vec<Wave>>,
+    rng: &mut impl Rng,
+    tuning: &GameTuning,
+    modifiers: DifficultyModifiers,
+) -> Vec<Wave> {
+    authored
+        .map(|waves| modifiers.scale_layout(waves))
+        .unwrap_or_else(|| scatter_waves(rng, &modifiers.scale_waves(tuning.waves(level)), level.enemy_heights()))
+}
+
+pub fn spawn_enemy(commands: &mut Commands, atlases: &AnimationAtlases, level: LevelState, spawn: &EnemySpawn, frame: usize) -> Entity {
+    let mut sprite = atlases.enemy_sprite();
+    if spawn.kind == EnemyKind::Armored {
+        sprite.color = ARMORED_TINT;
+    }
+    let mut enemy = commands.spawn((
+        sprite,
+        enemy_idle_animation(frame),
+        Transform::from_xyz(spawn.x, spawn.y, 0.5),
+        enemy_collider(),
+        spawn.kind,
+        EnemyHealth(spawn.kind.health()),
+        spawn.movement,
+        spawn.fire,
+        EnemyOrigin(Vec2::new(spawn.x, spawn.y)),
+        GameplayObject,
+        DespawnOnExit(level)
+    ));
+    match level {
+        LevelState::Level1 => enemy.insert(EnemiesStructInLevel1),
+        LevelState::Level2 => enemy.insert(EnemiesStructInLevel2),
+        LevelState::Level3 => enemy.insert(EnemiesStructInLevel3),
+    };
+    if let Some(script) = &spawn.script {
+        enemy.insert(ScriptedEnemy::new(script));
+    }
+    enemy.id()
+}
+
+impl LevelLayout<'_> {
+    pub fn waves(&self, level: LevelState) -> Option<Vec<Wave>> {
+        self.files.get(level, &self.assets).map(|file| file.waves.clone())
+    }
+
+    pub fn script(&self, level: LevelState) -> Option<String> {
+        self.files.get(level, &self.assets).and_then(|file| file.script.clone())
+    }
+}
+
+pub fn load_level_1(mut commands: Commands, atlases: Res<AnimationAtlases>,
+mut director: ResMut<WaveDirector>, mut rng: ResMut<GameRng>, layout: LevelLayout, tuning: Res<GameTuning>,
+                    challenge: Challenge) {
+    let waves = level_waves(LevelState::Level1, layout.waves(LevelState::Level1), &mut rng.0, &tuning, challenge.modifiers());
+    director.begin(LevelState::Level1, waves);
+    release_enemies(&mut commands, &atlases, &mut director, 0.0, &[]);
+}
+
+pub fn load_level_2(mut commands: Commands, atlases: Res<AnimationAtlases>,
+                    mut director: ResMut<WaveDirector>, mut rng: ResMut<GameRng>, layout: LevelLayout, tuning: Res<GameTuning>,
+                    challenge: Challenge) {
+    let waves = level_waves(LevelState::Level2, layout.waves(LevelState::Level2), &mut rng.0, &tuning, challenge.modifiers());
+    director.begin(LevelState::Level2, waves);
+    release_enemies(&mut commands, &atlases, &mut director, 0.0, &[]);
+}
+
+pub fn load_level_3(mut commands: Commands, atlases: Res<AnimationAtlases>,
+                    mut director: ResMut<WaveDirector>, mut rng: ResMut<GameRng>, layout: LevelLayout, tuning: Res<GameTuning>,
+                    challenge: Challenge) {
+    let waves = level_waves(LevelState::Level3, layout.waves(LevelState::Level3), &mut rng.0, &tuning, challenge.modifiers());
+    director.begin(LevelState::Level3, waves);
+    release_enemies(&mut commands, &atlases, &mut director, 0.0, &[]);
+}
+
+pub fn apply_enemy_definitions(
+    definitions: Res<EnemyDefinitions>,
+    mut enemies: Query<(&EnemyKind, &mut EnemyHealth, &mut Sprite), Added<EnemyKind>>,
+) {
+    for (kind, mut health, mut sprite) in enemies.iter_mut() {
+        let Some(definition) = definitions.0.get(kind) else {
+            continue;
+        };
+        if let Some(value) = definition.health {
+            health.0 = value;
+        }
+        if let Some((r, g, b)) = definition.tint {
+            sprite.color = Color::srgb(r, g, b);
+        }
+    }
+}
+
+pub fn move_patterned_enemies(
+    time: Res<Time>,
+    mut enemies: Query<(&EnemyMovement, &EnemyOrigin, &mut Transform), Without<EnemyEntry>>,
 ) {
-    for e in enemies.iter() {
-        commands.entity(e).despawn();
+    for (movement, origin, mut transform) in enemies.iter_mut() {
+        match movement {
+            EnemyMovement::Static => {}
+            EnemyMovement::Sway => {
+                transform.translation.x = origin.0.x + SWAY_AMPLITUDE * (time.elapsed_secs() * 2.0 + origin.0.y * 0.01).sin();
+            }
+            EnemyMovement::Descend => {
+                transform.translation.y = (transform.translation.y - DESCEND_SPEED * time.delta_secs()).max(DESCEND_FLOOR);
+            }
+        }
     }
-    spawned.0 = false;
 }
 
 pub fn check_level_1_complete(
+    mut commands: Commands,
     enemies: Query<&EnemiesStructInLevel1>,
     level_state: Res<State<LevelState>>,
-    spawned: Res<LevelEnemiesSpawned>,
-    mut next_level: ResMut<NextState<LevelState>>,
+    director: Res<WaveDirector>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut events: MessageWriter<GameplayEvent>,
 ) {
-    if spawned.0 && enemies.is_empty() && *level_state.get() == LevelState::Level1 {
-        next_level.set(LevelState::Level2);
+    if director.is_cleared(enemies.iter().len()) && *level_state.get() == LevelState::Level1 {
+        events.write(GameplayEvent::LevelCleared(LevelState::Level1));
+        begin_intermission(&mut commands, &mut next_state, LevelState::Level1, LevelState::Level2);
     }
 }
 
 pub fn check_level_2_complete(
+    mut commands: Commands,
     enemies: Query<&EnemiesStructInLevel2>,
     level_state: Res<State<LevelState>>,
-    spawned: Res<LevelEnemiesSpawned>,
-    mut next_level: ResMut<NextState<LevelState>>,
+    director: Res<WaveDirector>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut events: MessageWriter<GameplayEvent>,
 ) {
-    if spawned.0 && enemies.is_empty() && *level_state.get() == LevelState::Level2 {
-        next_level.set(LevelState::Level3);
+    if director.is_cleared(enemies.iter().len()) && *level_state.get() == LevelState::Level2 {
+        events.write(GameplayEvent::LevelCleared(LevelState::Level2));
+        begin_intermission(&mut commands, &mut next_state, LevelState::Level2, LevelState::Level3);
     }
 }
 
 pub fn distance_between_enemies_in_level_1(
     mut enemies_q: Query<(Entity, &mut Transform), With<EnemiesStructInLevel1>>,
+    tuning: Res<GameTuning>,
 ) {
-    let minimum_distance = 100.0;
+    let minimum_distance = tuning.spacing(LevelState::Level1);
     let enemies: Vec<(Entity, Vec3)> = enemies_q.iter().map(|(e, t)| (e, t.translation)).collect();
     for (entity_1, mut transform_1) in enemies_q.iter_mut() {
         for (entity_2, pos2) in &enemies {
@@ -148,8 +412,9 @@ pub fn distance_between_enemies_in_level_1(
 
 pub fn distance_between_enemies_in_level_2(
     mut enemies_q: Query<(Entity, &mut Transform), With<EnemiesStructInLevel2>>,
+    tuning: Res<GameTuning>,
 ) {
-    let minimum_distance = 80.0;
+    let minimum_distance = tuning.spacing(LevelState::Level2);
     let enemies: Vec<(Entity, Vec3)> = enemies_q.iter().map(|(e, t)| (e, t.translation)).collect();
     for (entity_1, mut transform_1) in enemies_q.iter_mut() {
         for (entity_2, pos2) in &enemies {
@@ -166,8 +431,9 @@ pub fn distance_between_enemies_in_level_2(
 
 pub fn distance_between_enemies_in_level_3(
     mut enemies_q: Query<(Entity, &mut Transform), With<EnemiesStructInLevel3>>,
+    tuning: Res<GameTuning>,
 ) {
-    let minimum_distance = 60.0;
+    let minimum_distance = tuning.spacing(LevelState::Level3);
     let enemies: Vec<(Entity, Vec3)> = enemies_q.iter().map(|(e, t)| (e, t.translation)).collect();
     for (entity_1, mut transform_1) in enemies_q.iter_mut() {
         for (entity_2, pos2) in &enemies {
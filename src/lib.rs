@@ -0,0 +1,70 @@
+#![allow(clippy::too_many_arguments)]
+
+pub mod achievements;
+pub mod adaptive;
+pub mod animation;
+pub mod background;
+pub mod bot;
+pub mod camera;
+pub mod console;
+pub mod debug;
+pub mod difficulty;
+pub mod editor;
+pub mod feel;
+pub mod game;
+pub mod headless;
+pub mod intermission;
+pub mod lasers_enemies;
+pub mod lasers_player;
+pub mod level_file;
+pub mod levels;
+pub mod mods;
+pub mod netplay;
+pub mod particles;
+pub mod physics;
+pub mod player;
+pub mod rl;
+pub mod scripting;
+pub mod stats;
+pub mod tuning;
+pub mod ui;
+pub mod versus;
+pub mod waves;
+
+use bevy::app::PluginGroupBuilder;
+use bevy::prelude::*;
+
+pub struct SpaceShooterPlugins;
+
+impl PluginGroup for SpaceShooterPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(game::GamePlugin)
+            .add(tuning::TuningPlugin)
+            .add(difficulty::DifficultyPlugin)
+            .add(adaptive::AdaptivePlugin)
+            .add(physics::PhysicsPlugin)
+            .add(stats::StatsPlugin)
+            .add(animation::AnimationPlugin)
+            .add(particles::ParticlesPlugin)
+            .add(feel::FeelPlugin)
+            .add(levels::LevelsPlugin)
+            .add(waves::WavesPlugin)
+            .add(scripting::ScriptingPlugin)
+            .add(player::PlayerPlugin)
+            .add(bot::BotPlugin)
+            .add(lasers_player::LasersPlayerPlugin)
+            .add(lasers_enemies::LasersEnemiesPlugin)
+            .add(intermission::IntermissionPlugin)
+            .add(camera::CameraPlugin)
+            .add(background::BackgroundPlugin)
+            .add(versus::VersusPlugin)
+            .add(ui::UiPlugin)
+            .add(netplay::NetplayPlugin)
+            .add(editor::EditorPlugin)
+            .add(mods::ModsPlugin)
+            .add(console::ConsolePlugin)
+            .add(debug::DebugPlugin)
+            .add(achievements::AchievementsPlugin)
+    }
+}
@@ -0,0 +1,505 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use bevy::asset::io::file::{FileAssetReader, FileWatcher};
+use bevy::asset::io::{AssetReader, AssetReaderError, AssetSourceBuilder, AssetSourceId, AssetWatcher, PathStream, Reader, VecReader};
+use bevy::prelude::*;
+use bevy::tasks::futures_lite::stream;
+use serde::{Deserialize, Serialize};
+use crate::game::GameState;
+use crate::level_file::*;
+use crate::levels::*;
+use crate::scripting::{SCRIPT_DIRECTORY, SCRIPT_EXTENSION, Script};
+use crate::ui::Localization;
+
+pub const MODS_DIRECTORY: &str = "mods";
+pub const MOD_MANIFEST: &str = "mod.ron";
+pub const MOD_ENEMIES: &str = "enemies.ron";
+pub const MOD_STRINGS: &str = "strings.ron";
+pub const ENABLED_MODS: &str = "enabled.ron";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct ModManifest {
+    pub name: String,
+    pub version: String,
+    #[serde(default)]
+    pub load_order: i32,
+    #[serde(default)]
+    pub levels: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct GameMod {
+    pub folder: String,
+    pub path: PathBuf,
+    pub manifest: Option<ModManifest>,
+    pub enemies: HashMap<EnemyKind, EnemyDefinition>,
+    pub strings: HashMap<String, String>,
+    pub errors: Vec<String>,
+    pub enabled: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ModContent {
+    pub levels: Vec<String>,
+    pub enemies: HashMap<EnemyKind, EnemyDefinition>,
+    pub strings: HashMap<String, String>,
+}
+
+#[derive(Clone, Default)]
+pub struct ModLayers(pub Arc<RwLock<Vec<PathBuf>>>);
+
+#[derive(Resource, Default)]
+pub struct ModRegistry {
+    pub root: PathBuf,
+    pub mods: Vec<GameMod>,
+    pub layers: ModLayers,
+    pub selected: usize,
+    pub mod_levels: bool,
+}
+
+#[derive(Component)]
+pub struct ModMenuText;
+
+fn read_ron<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Option<T>, String> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let bytes = std::fs::read(path).map_err(|error| error.to_string())?;
+    ron::de::from_bytes(&bytes).map(Some).map_err(|error| error.to_string())
+}
+
+fn files_in(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .collect();
+    files.sort();
+    files
+}
+
+fn valid_version(version: &str) -> bool {
+    let parts: Vec<&str> = version.split('.').collect();
+    parts.len() == 3 && parts.iter().all(|part| part.parse::<u32>().is_ok())
+}
+
+impl GameMod {
+    pub fn load(path: &Path) -> Self {
+        let folder = path.file_name().map_or(String::new(), |name| name.to_string_lossy().into_owned());
+        let mut game_mod = GameMod { folder, path: path.to_path_buf(), ..default() };
+        let errors = &mut game_mod.errors;
+
+        match read_ron::<ModManifest>(&path.join(MOD_MANIFEST)) {
+            Ok(Some(manifest)) => {
+                if manifest.name.trim().is_empty() {
+                    errors.push("порожня назва".to_string());
+                }
+                if !valid_version(&manifest.version) {
+                    errors.push(format!("версія «{}» не у форматі 1.0.0", manifest.version));
+                }
+                for level in &manifest.levels {
+                    if !level.ends_with(LEVEL_FILE_EXTENSION) {
+                        errors.push(format!("{level}: рівень має закінчуватися на .{LEVEL_FILE_EXTENSION}"));
+                    } else if !path.join(level).is_file() {
+                        errors.push(format!("{level}: файл не знайдено"));
+                    }
+                }
+                game_mod.manifest = Some(manifest);
+            }
+            Ok(None) => errors.push(format!("немає {MOD_MANIFEST}")),
+            Err(error) => errors.push(format!("{MOD_MANIFEST}: {error}")),
+        }
+
+        for level in files_in(&path.join("levels")) {
+            if level.to_string_lossy().ends_with(LEVEL_FILE_EXTENSION)
+                && let Err(error) = LevelFile::read(&level) {
+                errors.push(format!("levels/{}: {error}", level.file_name().unwrap().to_string_lossy()));
+            }
+        }
+        let engine = rhai::Engine::new();
+        for script in files_in(&path.join(SCRIPT_DIRECTORY)) {
+            let name = script.file_name().unwrap().to_string_lossy().into_owned();
+            if script.extension().is_some_and(|e| e == SCRIPT_EXTENSION)
+                && let Err(error) = engine.compile_file(script) {
+                errors.push(format!("{SCRIPT_DIRECTORY}/{name}: {error}"));
+            }
+        }
+        for (dir, extension) in [("images", "png"), ("sounds", "ogg")] {
+            for file in files_in(&path.join(dir)) {
+                if file.extension().is_none_or(|e| e != extension) {
+                    errors.push(format!("{dir}/{}: очікується .{extension}", file.file_name().unwrap().to_string_lossy()));
+                }
+            }
+        }
+
+        match read_ron::<HashMap<EnemyKind, EnemyDefinition>>(&path.join(MOD_ENEMIES)) {
+            Ok(enemies) => {
+                let enemies = enemies.unwrap_or_default();
+                for (kind, definition) in &enemies {
+                    if definition.health == Some(0) {
+                        errors.push(format!("{MOD_ENEMIES}: {kind:?} має нульове здоров’я"));
+                    }
+                }
+                game_mod.enemies = enemies;
+            }
+            Err(error) => errors.push(format!("{MOD_ENEMIES}: {error}")),
+        }
+
+        match read_ron::<HashMap<String, String>>(&path.join(MOD_STRINGS)) {
+            Ok(strings) => {
+                let strings = strings.unwrap_or_default();
+                let known = Localization::default();
+                for key in strings.keys() {
+                    if !known.0.contains_key(key) {
+                        errors.push(format!("{MOD_STRINGS}: невідомий ключ «{key}»"));
+                    }
+                }
+                game_mod.strings = strings;
+            }
+            Err(error) => errors.push(format!("{MOD_STRINGS}: {error}")),
+        }
+        game_mod
+    }
+
+    pub fn name(&self) -> &str {
+        self.manifest.as_ref().map_or(&self.folder, |m| &m.name)
+    }
+
+    pub fn load_order(&self) -> i32 {
+        self.manifest.as_ref().map_or(0, |m| m.load_order)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl ModRegistry {
+    pub fn discover(root: &Path) -> Self {
+        let mut mods: Vec<GameMod> = std::fs::read_dir(root)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .map(|path| GameMod::load(&path))
+            .collect();
+        mods.sort_by(|a, b| a.load_order().cmp(&b.load_order()).then_with(|| a.folder.cmp(&b.folder)));
+
+        for i in 1..mods.len() {
+            if mods[..i].iter().any(|m| m.manifest.is_some() && m.name() == mods[i].name()) {
+                let name = mods[i].name().to_string();
+                mods[i].errors.push(format!("мод «{name}» уже встановлено"));
+            }
+        }
+
+        let enabled: Vec<String> = read_ron(&root.join(ENABLED_MODS)).ok().flatten().unwrap_or_default();
+        for game_mod in &mut mods {
+            game_mod.enabled = game_mod.is_valid() && enabled.contains(&game_mod.folder);
+        }
+        let registry = Self { root: root.to_path_buf(), mods, ..default() };
+        registry.update_layers();
+        registry
+    }
+
+    pub fn enabled(&self) -> impl DoubleEndedIterator<Item = &GameMod> {
+        self.mods.iter().filter(|m| m.enabled)
+    }
+
+    pub fn toggle(&mut self, index: usize) {
+        if let Some(game_mod) = self.mods.get_mut(index) {
+            game_mod.enabled = !game_mod.enabled && game_mod.is_valid();
+        }
+    }
+
+    pub fn update_layers(&self) {
+        *self.layers.0.write().unwrap() = self.enabled().rev().map(|m| m.path.clone()).collect();
+    }
+
+    pub fn content(&self) -> ModContent {
+        let mut content = ModContent::default();
+        for game_mod in self.enabled() {
+            if let Some(manifest) = &game_mod.manifest
+                && !manifest.levels.is_empty() {
+                content.levels = manifest.levels.clone();
+            }
+            content.enemies.extend(game_mod.enemies.clone());
+            content.strings.extend(game_mod.strings.clone());
+        }
+        content
+    }
+
+    pub fn save(&self) -> std::io::Result<()> {
+        if self.mods.is_empty() {
+            return Ok(());
+        }
+        let enabled: Vec<&String> = self.enabled().map(|m| &m.folder).collect();
+        let ron = ron::ser::to_string(&enabled).map_err(std::io::Error::other)?;
+        std::fs::write(self.root.join(ENABLED_MODS), ron)
+    }
+}
+
+pub struct LayeredAssetReader {
+    layers: ModLayers,
+    base: PathBuf,
+}
+
+impl LayeredAssetReader {
+    fn roots(&self) -> Vec<PathBuf> {
+        let mut roots = self.layers.0.read().unwrap().clone();
+        roots.push(self.base.clone());
+        roots
+    }
+
+    fn find(&self, path: &Path) -> Result<PathBuf, AssetReaderError> {
+        self.roots().into_iter()
+            .map(|root| root.join(path))
+            .find(|full| full.is_file())
+            .ok_or_else(|| AssetReaderError::NotFound(self.base.join(path)))
+    }
+}
+
+impl AssetReader for LayeredAssetReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        Ok(VecReader::new(std::fs::read(self.find(path)?)?))
+    }
+
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<impl Reader + 'a, AssetReaderError> {
+        let meta = PathBuf::from(format!("{}.meta", path.display()));
+        Ok(VecReader::new(std::fs::read(self.find(&meta)?)?))
+    }
+
+    async fn read_directory<'a>(&'a self, path: &'a Path) -> Result<Box<PathStream>, AssetReaderError> {
+        let mut found = false;
+        let mut entries: Vec<PathBuf> = Vec::new();
+        for root in self.roots() {
+            let Ok(dir) = std::fs::read_dir(root.join(path)) else {
+                continue;
+            };
+            found = true;
+            for entry in dir.flatten() {
+                let relative = path.join(entry.file_name());
+                if relative.extension().is_some_and(|e| e == "meta") || entries.contains(&relative) {
+                    continue;
+                }
+                entries.push(relative);
+            }
+        }
+        if !found {
+            return Err(AssetReaderError::NotFound(self.base.join(path)));
+        }
+        Ok(Box::new(stream::iter(entries)))
+    }
+
+    async fn is_directory<'a>(&'a self, path: &'a Path) -> Result<bool, AssetReaderError> {
+        Ok(self.roots().iter().any(|root| root.join(path).is_dir()))
+    }
+}
+
+pub struct LayeredWatcher(pub Vec<FileWatcher>);
+
+impl AssetWatcher for LayeredWatcher {}
+
+pub struct ModSourcePlugin {
+    pub root: PathBuf,
+}
+
+impl Default for ModSourcePlugin {
+    fn default() -> Self {
+        Self { root: FileAssetReader::get_base_path().join(MODS_DIRECTORY) }
+    }
+}
+
+impl ModSourcePlugin {
+    pub fn none() -> Self {
+        Self { root: PathBuf::new() }
+    }
+}
+
+impl Plugin for ModSourcePlugin {
+    fn build(&self, app: &mut App) {
+        let registry = ModRegistry::discover(&self.root);
+        let layers = registry.layers.clone();
+        let base = FileAssetReader::get_base_path().join("assets");
+        let mut watched = vec![base.clone()];
+        watched.extend(registry.mods.iter().map(|m| m.path.clone()));
+
+        app.register_asset_source(
+            AssetSourceId::Default,
+            AssetSourceBuilder::new(move || Box::new(LayeredAssetReader { layers: layers.clone(), base: base.clone() }))
+                .with_watcher(move |sender| {
+                    let watchers = watched.iter()
+                        .filter(|path| path.is_dir())
+                        .filter_map(|path| FileWatcher::new(path.clone(), sender.clone(), Duration::from_millis(300)).ok())
+                        .collect();
+                    Some(Box::new(LayeredWatcher(watchers)))
+                }),
+        );
+        let content = registry.content();
+        app
+            .insert_resource(Localization::with_overrides(&content.strings))
+            .insert_resource(EnemyDefinitions(content.enemies))
+            .insert_resource(registry);
+    }
+}
+
+pub struct ModsPlugin;
+
+impl Plugin for ModsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<ModRegistry>()
+            .add_systems(Startup, apply_mod_levels.before(crate::editor::apply_editor_config))
+            .add_systems(Update, open_mod_menu.run_if(in_state(GameState::NotStarted)))
+            .add_systems(OnEnter(GameState::Mods), show_mod_menu)
+            .add_systems(Update, (mod_menu_keys, update_mod_menu_text)
+                .chain()
+                .run_if(in_state(GameState::Mods)))
+            .add_systems(OnExit(GameState::Mods), apply_mods);
+    }
+}
+
+fn set_mod_levels(asset_server: &AssetServer, registry: &mut ModRegistry, files: &mut LevelFiles) {
+    let levels = registry.content().levels;
+    if !levels.is_empty() || registry.mod_levels {
+        files.levels = levels.iter().map(|path| asset_server.load(path.clone())).collect();
+        registry.mod_levels = !levels.is_empty();
+    }
+}
+
+pub fn apply_mod_levels(
+    asset_server: Res<AssetServer>,
+    mut registry: ResMut<ModRegistry>,
+    mut files: ResMut<LevelFiles>,
+) {
+    set_mod_levels(&asset_server, &mut registry, &mut files);
+}
+
+fn reload_all<A: Asset>(asset_server: &AssetServer, assets: &Assets<A>) {
+    for id in assets.ids() {
+        if let Some(path) = asset_server.get_path(id) {
+            asset_server.reload(path.into_owned());
+        }
+    }
+}
+
+pub fn apply_mods(
+    asset_server: Res<AssetServer>,
+    mut registry: ResMut<ModRegistry>,
+    mut files: ResMut<LevelFiles>,
+    mut localization: ResMut<Localization>,
+    mut definitions: ResMut<EnemyDefinitions>,
+    images: Res<Assets<Image>>,
+    sounds: Res<Assets<AudioSource>>,
+    levels: Res<Assets<LevelFile>>,
+    scripts: Res<Assets<Script>>,
+) {
+    registry.update_layers();
+    if let Err(error) = registry.save() {
+        warn!("не вдалося зберегти список модів: {error}");
+    }
+    let content = registry.content();
+    *localization = Localization::with_overrides(&content.strings);
+    definitions.0 = content.enemies;
+    reload_all(&asset_server, &images);
+    reload_all(&asset_server, &sounds);
+    reload_all(&asset_server, &levels);
+    reload_all(&asset_server, &scripts);
+    set_mod_levels(&asset_server, &mut registry, &mut files);
+}
+
+pub fn open_mod_menu(
+    key_code: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if key_code.just_pressed(KeyCode::KeyM) {
+        next_state.set(GameState::Mods);
+    }
+}
+
+pub fn show_mod_menu(mut commands: Commands, asset_server: Res<AssetServer>, mut registry: ResMut<ModRegistry>) {
+    registry.set_changed();
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        DespawnOnExit(GameState::Mods),
+    )).with_children(|parent| {
+        parent.spawn((
+            Text::new(""),
+            TextFont {
+                font: asset_server.load("fonts/e-UkraineHead-Medium.otf"),
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            ModMenuText,
+        ));
+    });
+}
+
+pub fn mod_menu_keys(
+    key_code: Res<ButtonInput<KeyCode>>,
+    mut registry: ResMut<ModRegistry>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if key_code.just_pressed(KeyCode::Escape) || key_code.just_pressed(KeyCode::KeyM) {
+        next_state.set(GameState::NotStarted);
+        return;
+    }
+    let count = registry.mods.len();
+    if count == 0 {
+        return;
+    }
+    if key_code.just_pressed(KeyCode::ArrowDown) {
+        registry.selected = (registry.selected + 1) % count;
+    }
+    if key_code.just_pressed(KeyCode::ArrowUp) {
+        registry.selected = (registry.selected + count - 1) % count;
+    }
+    if key_code.just_pressed(KeyCode::Enter) || key_code.just_pressed(KeyCode::Space) {
+        let selected = registry.selected;
+        registry.toggle(selected);
+    }
+}
+
+pub fn mod_menu_text(registry: &ModRegistry) -> String {
+    let mut lines = vec![format!("Моди (теки в {MODS_DIRECTORY}/)"), String::new()];
+    if registry.mods.is_empty() {
+        lines.push("Модів не знайдено".to_string());
+    }
+    for (i, game_mod) in registry.mods.iter().enumerate() {
+        let cursor = if i == registry.selected { ">" } else { " " };
+        let mark = if game_mod.enabled { "[x]" } else { "[ ]" };
+        let version = game_mod.manifest.as_ref().map_or("?", |m| m.version.as_str());
+        lines.push(format!(
+            "{cursor} {mark} {} {version} — порядок {} ({})",
+            game_mod.name(),
+            game_mod.load_order(),
+            game_mod.folder
+        ));
+        lines.extend(game_mod.errors.iter().map(|error| format!("      ! {error}")));
+    }
+    lines.push(String::new());
+    lines.push("↑/↓ — вибір, Enter — увімкнути/вимкнути, Esc — назад".to_string());
+    lines.join("\n")
+}
+
+pub fn update_mod_menu_text(registry: Res<ModRegistry>, mut text_query: Query<&mut Text, With<ModMenuText>>) {
+    if !registry.is_changed() {
+        return;
+    }
+    let text = mod_menu_text(&registry);
+    for mut menu_text in text_query.iter_mut() {
+        menu_text.0 = text.clone();
+    }
+}
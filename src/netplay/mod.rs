@@ -0,0 +1,396 @@
+pub mod rollback;
+pub mod session;
+pub mod sim;
+pub mod transport;
+
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use bevy::ecs::system::SystemParam;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use crate::animation::*;
+use crate::difficulty::Difficulty;
+use crate::game::GameState;
+use crate::levels::{EnemyDefinitions, LevelLayout, LevelState};
+use crate::player::{player_color, PlayerId};
+use crate::tuning::GameTuning;
+pub use session::*;
+pub use sim::*;
+pub use transport::NetConditions;
+
+pub const DEFAULT_NET_ADDRESS: &str = "127.0.0.1:7000";
+pub const NET_CONNECTION_LOST: &str = "З'єднання втрачено";
+
+#[derive(Resource)]
+pub struct NetMenu {
+    pub address: String,
+    pub preset: usize,
+    pub status: String,
+}
+
+impl Default for NetMenu {
+    fn default() -> Self {
+        Self {
+            address: DEFAULT_NET_ADDRESS.to_string(),
+            preset: 0,
+            status: String::new(),
+        }
+    }
+}
+
+impl NetMenu {
+    pub fn conditions(&self) -> NetConditions {
+        NetConditions::presets()[self.preset]
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct PendingNetInput(pub NetInput);
+
+#[derive(SystemParam)]
+pub struct NetRules<'w> {
+    tuning: Res<'w, GameTuning>,
+    difficulty: Res<'w, Difficulty>,
+    layout: LevelLayout<'w>,
+    definitions: Res<'w, EnemyDefinitions>,
+}
+
+impl NetRules<'_> {
+    pub fn rules(&self) -> SimRules {
+        let modifiers = self.difficulty.modifiers();
+        SimRules::new(&self.tuning, modifiers)
+            .with_layouts(LevelState::ALL.map(|level| self.layout.waves(level).map(|waves| modifiers.scale_layout(waves))))
+            .with_enemy_definitions(&self.definitions)
+    }
+}
+
+#[derive(Component)]
+pub struct NetMenuText;
+
+#[derive(Component)]
+pub struct NetHudText;
+
+#[derive(Component)]
+pub struct NetView(pub u32);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NetViewKind {
+    Player(usize),
+    Enemy,
+    PlayerLaser,
+    EnemyLaser,
+}
+
+pub struct NetplayPlugin;
+
+impl Plugin for NetplayPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_message::<KeyboardInput>()
+            .init_resource::<NetMenu>()
+            .init_resource::<PendingNetInput>()
+            .add_systems(Startup, launch_from_config)
+            .add_systems(Update, open_net_menu.run_if(in_state(GameState::NotStarted)))
+            .add_systems(OnEnter(GameState::NetMenu), show_net_menu)
+            .add_systems(Update, (edit_net_menu, poll_net_handshake, update_net_menu_text)
+                .chain()
+                .run_if(in_state(GameState::NetMenu)))
+            .add_systems(OnEnter(GameState::Online), show_net_hud)
+            .add_systems(OnExit(GameState::Online), close_net_session)
+            .add_systems(Update, collect_net_input.run_if(in_state(GameState::Online)))
+            .add_systems(FixedUpdate, advance_net_session.run_if(in_state(GameState::Online)))
+            .add_systems(Update, (sync_net_view, update_net_hud, check_net_outcome)
+                .chain()
+                .run_if(in_state(GameState::Online)));
+    }
+}
+
+pub fn launch_from_config(
+    mut commands: Commands,
+    config: Option<Res<NetConfig>>,
+    rules: NetRules,
+    mut menu: ResMut<NetMenu>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(config) = config else {
+        return;
+    };
+    if let NetRole::Join(address) = config.role {
+        menu.address = address.to_string();
+    }
+    start_session(&mut commands, &config, rules.rules(), &mut menu);
+    next_state.set(GameState::NetMenu);
+}
+
+fn start_session(commands: &mut Commands, config: &NetConfig, rules: SimRules, menu: &mut NetMenu) {
+    match NetSession::start(config, rules) {
+        Ok(session) => {
+            menu.status = match (config.role, session.local_addr()) {
+                (NetRole::Host, Ok(local)) => format!("Очікування гравця на порту {}...", local.port()),
+                (NetRole::Join(address), _) => format!("Підключення до {address}..."),
+                (NetRole::Host, Err(_)) => "Очікування гравця...".to_string(),
+            };
+            commands.insert_resource(session);
+        }
+        Err(error) => {
+            menu.status = format!("Помилка мережі: {error}");
+        }
+    }
+}
+
+pub fn open_net_menu(
+    key_code: Res<ButtonInput<KeyCode>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if key_code.just_pressed(KeyCode::KeyN) {
+        next_state.set(GameState::NetMenu);
+    }
+}
+
+pub fn show_net_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        DespawnOnExit(GameState::NetMenu),
+    )).with_children(|parent| {
+        parent.spawn((
+            Text::new(""),
+            TextFont {
+                font: asset_server.load("fonts/e-UkraineHead-Medium.otf"),
+                font_size: 25.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+            NetMenuText,
+        ));
+    });
+}
+
+pub fn edit_net_menu(
+    mut commands: Commands,
+    key_code: Res<ButtonInput<KeyCode>>,
+    mut typed: MessageReader<KeyboardInput>,
+    mut menu: ResMut<NetMenu>,
+    session: Option<Res<NetSession>>,
+    rules: NetRules,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if key_code.just_pressed(KeyCode::Escape) {
+        commands.remove_resource::<NetSession>();
+        menu.status.clear();
+        next_state.set(GameState::NotStarted);
+        return;
+    }
+    if session.is_some() {
+        typed.clear();
+        return;
+    }
+
+    for event in typed.read().filter(|e| e.state.is_pressed()) {
+        if let Key::Character(c) = &event.logical_key
+            && c.chars().all(|c| c.is_ascii_digit() || c == '.' || c == ':') {
+            menu.address.push_str(c);
+        }
+    }
+    if key_code.just_pressed(KeyCode::Backspace) {
+        menu.address.pop();
+    }
+    if key_code.just_pressed(KeyCode::KeyL) {
+        menu.preset = (menu.preset + 1) % NetConditions::presets().len();
+    }
+
+    let address: Result<SocketAddr, _> = menu.address.parse();
+    let rules = rules.rules();
+    if key_code.just_pressed(KeyCode::KeyH) {
+        let port = address.map_or(7000, |a| a.port());
+        let config = NetConfig { conditions: menu.conditions(), ..NetConfig::host(port) };
+        start_session(&mut commands, &config, rules, &mut menu);
+    } else if key_code.just_pressed(KeyCode::KeyJ) {
+        match address {
+            Ok(address) => {
+                let config = NetConfig { conditions: menu.conditions(), ..NetConfig::join(address, 0) };
+                start_session(&mut commands, &config, rules, &mut menu);
+            }
+            Err(_) => menu.status = "Некоректна адреса, очікується IP:порт".to_string(),
+        }
+    }
+}
+
+pub fn poll_net_handshake(
+    session: Option<ResMut<NetSession>>,
+    rules: NetRules,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(mut session) = session else {
+        return;
+    };
+    session.offer_rules(rules.rules());
+    session.poll();
+    if session.is_running() {
+        next_state.set(GameState::Online);
+    }
+}
+
+pub fn update_net_menu_text(menu: Res<NetMenu>, mut text_query: Query<&mut Text, With<NetMenuText>>) {
+    let conditions = menu.conditions();
+    for mut text in text_query.iter_mut() {
+        text.0 = format!(
+            "Мережева гра\n\nАдреса: {}_\nВтрати пакетів: {:.0}%, затримка: {} мс (L — змінити)\n\nH — створити гру, J — приєднатися, Esc — назад\n\n{}",
+            menu.address,
+            conditions.loss * 100.0,
+            conditions.latency.as_millis(),
+            menu.status
+        );
+    }
+}
+
+pub fn collect_net_input(key_code: Res<ButtonInput<KeyCode>>, mut pending: ResMut<PendingNetInput>) {
+    let input = NetInput::new(
+        key_code.just_pressed(KeyCode::KeyA) || key_code.just_pressed(KeyCode::ArrowLeft),
+        key_code.just_pressed(KeyCode::KeyD) || key_code.just_pressed(KeyCode::ArrowRight),
+        key_code.just_pressed(KeyCode::Space),
+    );
+    pending.0 = pending.0.merge(input);
+}
+
+pub fn advance_net_session(mut session: ResMut<NetSession>, mut pending: ResMut<PendingNetInput>) {
+    if session.tick(pending.0) {
+        pending.0 = NetInput::default();
+    }
+}
+
+pub fn close_net_session(
+    mut commands: Commands,
+    session: Option<Res<NetSession>>,
+    mut menu: ResMut<NetMenu>,
+    mut pending: ResMut<PendingNetInput>,
+) {
+    commands.remove_resource::<NetSession>();
+    menu.status = if session.is_some_and(|session| session.timed_out()) {
+        NET_CONNECTION_LOST.to_string()
+    } else {
+        String::new()
+    };
+    pending.0 = NetInput::default();
+}
+
+pub fn net_view_objects(state: &SimState) -> Vec<(u32, NetViewKind, Vec2)> {
+    let mut objects: Vec<(u32, NetViewKind, Vec2)> = state.players.iter().enumerate()
+        .map(|(i, p)| (i as u32, NetViewKind::Player(i), Vec2::new(p.x, -370.0)))
+        .collect();
+    objects.extend(state.enemies.iter().map(|e| (e.id, NetViewKind::Enemy, e.pos)));
+    objects.extend(state.player_lasers.iter().map(|l| (l.id, NetViewKind::PlayerLaser, l.pos)));
+    objects.extend(state.enemy_lasers.iter().map(|l| (l.id, NetViewKind::EnemyLaser, l.pos)));
+    objects
+}
+
+pub fn sync_net_view(
+    mut commands: Commands,
+    session: Res<NetSession>,
+    atlases: Res<AnimationAtlases>,
+    asset_server: Res<AssetServer>,
+    mut views: Query<(Entity, &NetView, &mut Transform, &mut Sprite)>,
+) {
+    let Some(rollback) = session.rollback.as_ref() else {
+        return;
+    };
+    let state = &rollback.state;
+    let objects = net_view_objects(state);
+    let alive: HashSet<u32> = objects.iter().map(|(id, _, _)| *id).collect();
+    let mut existing = HashMap::new();
+
+    for (entity, view, mut transform, mut sprite) in views.iter_mut() {
+        if !alive.contains(&view.0) {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        existing.insert(view.0, entity);
+        if let Some((_, kind, pos)) = objects.iter().find(|(id, _, _)| *id == view.0) {
+            transform.translation.x = pos.x;
+            transform.translation.y = pos.y;
+            if let NetViewKind::Player(i) = kind {
+                let alpha = if state.players[*i].downed { 0.3 } else { 1.0 };
+                sprite.color = player_color(PlayerId(*i)).with_alpha(alpha);
+            }
+        }
+    }
+
+    for (id, kind, pos) in objects.into_iter().filter(|(id, _, _)| !existing.contains_key(id)) {
+        let transform = Transform::from_xyz(pos.x, pos.y, 0.5);
+        let view = (NetView(id), transform, DespawnOnExit(GameState::Online));
+        match kind {
+            NetViewKind::Player(i) => {
+                let mut sprite = atlases.player_sprite();
+                sprite.color = player_color(PlayerId(i));
+                commands.spawn((sprite, view));
+            }
+            NetViewKind::Enemy => {
+                commands.spawn((atlases.enemy_sprite(), enemy_idle_animation(id as usize % 6), view));
+            }
+            NetViewKind::PlayerLaser => {
+                commands.spawn((Sprite::from_image(asset_server.load("images/lasers/laserGreen.png")), view));
+            }
+            NetViewKind::EnemyLaser => {
+                commands.spawn((Sprite::from_image(asset_server.load("images/lasers/laserRed.png")), view));
+            }
+        }
+    }
+}
+
+pub fn show_net_hud(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font: asset_server.load("fonts/e-UkraineHead-Medium.otf"),
+            font_size: 18.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..default()
+        },
+        NetHudText,
+        DespawnOnExit(GameState::Online),
+    ));
+}
+
+pub fn update_net_hud(session: Res<NetSession>, mut text_query: Query<&mut Text, With<NetHudText>>) {
+    let Some(rollback) = session.rollback.as_ref() else {
+        return;
+    };
+    let state = &rollback.state;
+    let mut hud = format!("Рівень {}\n", state.level);
+    for (i, player) in state.players.iter().enumerate() {
+        let you = if i == rollback.local { " (ви)" } else { "" };
+        hud += &format!("Гравець {}{}: {} (життів: {})\n", i + 1, you, player.score, player.lives);
+    }
+    hud += &format!("Кадр: {}, відкатів: {}", state.frame, rollback.rollbacks);
+    for mut text in text_query.iter_mut() {
+        text.0 = hud.clone();
+    }
+}
+
+pub fn check_net_outcome(session: Res<NetSession>, mut next_state: ResMut<NextState<GameState>>) {
+    if session.timed_out() {
+        next_state.set(GameState::NetMenu);
+        return;
+    }
+    let Some(rollback) = session.rollback.as_ref().filter(|r| r.is_confirmed()) else {
+        return;
+    };
+    match rollback.state.outcome {
+        SimOutcome::Won => next_state.set(GameState::Win),
+        SimOutcome::Lost => next_state.set(GameState::GameOver),
+        SimOutcome::Running => {}
+    }
+}
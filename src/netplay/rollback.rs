@@ -0,0 +1,131 @@
+use std::collections::{BTreeMap, VecDeque};
+use super::sim::*;
+
+pub const INPUT_DELAY: u32 = 2;
+pub const MAX_ROLLBACK_FRAMES: u32 = 12;
+
+pub struct Rollback {
+    pub local: usize,
+    pub state: SimState,
+    saved: VecDeque<SimState>,
+    local_inputs: BTreeMap<u32, NetInput>,
+    remote_inputs: BTreeMap<u32, NetInput>,
+    predicted: BTreeMap<u32, NetInput>,
+    remote_received_until: u32,
+    peer_received_until: u32,
+    pending_rollback: Option<u32>,
+    pub rollbacks: u32,
+}
+
+impl Rollback {
+    pub fn new(local: usize, seed: u64, rules: SimRules) -> Self {
+        let mut rollback = Self {
+            local,
+            state: SimState::new(seed, rules),
+            saved: VecDeque::new(),
+            local_inputs: BTreeMap::new(),
+            remote_inputs: BTreeMap::new(),
+            predicted: BTreeMap::new(),
+            remote_received_until: 0,
+            peer_received_until: 0,
+            pending_rollback: None,
+            rollbacks: 0,
+        };
+        for frame in 0..INPUT_DELAY {
+            rollback.local_inputs.insert(frame, NetInput::default());
+            rollback.add_remote_input(frame, NetInput::default());
+        }
+        rollback
+    }
+
+    pub fn frame(&self) -> u32 {
+        self.state.frame
+    }
+
+    pub fn remote_received_until(&self) -> u32 {
+        self.remote_received_until
+    }
+
+    pub fn is_confirmed(&self) -> bool {
+        self.frame() <= self.remote_received_until
+    }
+
+    pub fn can_advance(&self) -> bool {
+        self.frame() < self.remote_received_until + MAX_ROLLBACK_FRAMES
+    }
+
+    pub fn add_local_input(&mut self, input: NetInput) -> u32 {
+        let frame = self.frame() + INPUT_DELAY;
+        self.local_inputs.insert(frame, input);
+        frame
+    }
+
+    pub fn unacknowledged_local_inputs(&self) -> Vec<(u32, NetInput)> {
+        self.local_inputs.range(self.peer_received_until..).map(|(f, i)| (*f, *i)).collect()
+    }
+
+    pub fn acknowledge_local(&mut self, peer_received_until: u32) {
+        self.peer_received_until = self.peer_received_until.max(peer_received_until);
+    }
+
+    pub fn add_remote_input(&mut self, frame: u32, input: NetInput) {
+        if frame < self.remote_received_until || self.remote_inputs.contains_key(&frame) {
+            return;
+        }
+        self.remote_inputs.insert(frame, input);
+        while self.remote_inputs.contains_key(&self.remote_received_until) {
+            self.remote_received_until += 1;
+        }
+
+        if frame < self.frame() && self.predicted.get(&frame) != Some(&input) {
+            self.pending_rollback = Some(self.pending_rollback.map_or(frame, |f| f.min(frame)));
+        }
+    }
+
+    fn inputs_for(&mut self, frame: u32) -> [NetInput; NET_PLAYERS] {
+        let local = self.local_inputs.get(&frame).copied().unwrap_or_default();
+        let remote = self.remote_inputs.get(&frame).copied().unwrap_or_default();
+        self.predicted.insert(frame, remote);
+        let mut inputs = [remote; NET_PLAYERS];
+        inputs[self.local] = local;
+        inputs
+    }
+
+    fn simulate_frame(&mut self) {
+        let frame = self.frame();
+        let inputs = self.inputs_for(frame);
+        self.saved.push_back(self.state.clone());
+        while self.saved.len() > MAX_ROLLBACK_FRAMES as usize + 1 {
+            self.saved.pop_front();
+        }
+        self.state.step(inputs);
+    }
+
+    pub fn apply_rollback(&mut self) {
+        let Some(target) = self.pending_rollback.take() else {
+            return;
+        };
+        let current = self.frame();
+        let oldest = current - self.saved.len() as u32;
+        if target < oldest {
+            return;
+        }
+        let index = (target - oldest) as usize;
+        self.state = self.saved[index].clone();
+        self.saved.truncate(index);
+        self.rollbacks += 1;
+        while self.frame() < current {
+            self.simulate_frame();
+        }
+    }
+
+    pub fn advance(&mut self) {
+        self.apply_rollback();
+        self.simulate_frame();
+
+        let keep_from = self.frame().saturating_sub(MAX_ROLLBACK_FRAMES + 1);
+        self.predicted = self.predicted.split_off(&keep_from);
+        self.remote_inputs = self.remote_inputs.split_off(&keep_from.min(self.remote_received_until));
+        self.local_inputs = self.local_inputs.split_off(&keep_from.min(self.peer_received_until));
+    }
+}
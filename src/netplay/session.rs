@@ -0,0 +1,204 @@
+use std::io;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::{Duration, Instant};
+use bevy::prelude::*;
+use rand::random;
+use super::rollback::Rollback;
+use super::sim::{NetInput, SimRules};
+use super::transport::*;
+
+const HELLO_INTERVAL: Duration = Duration::from_millis(200);
+const PEER_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_INPUTS_PER_PACKET: usize = 256;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum NetRole {
+    Host,
+    Join(SocketAddr),
+}
+
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct NetConfig {
+    pub role: NetRole,
+    pub bind: SocketAddr,
+    pub conditions: NetConditions,
+}
+
+impl NetConfig {
+    pub fn host(port: u16) -> Self {
+        Self {
+            role: NetRole::Host,
+            bind: SocketAddr::from((Ipv4Addr::UNSPECIFIED, port)),
+            conditions: NetConditions::default(),
+        }
+    }
+
+    pub fn join(address: SocketAddr, port: u16) -> Self {
+        Self {
+            role: NetRole::Join(address),
+            bind: SocketAddr::from((Ipv4Addr::UNSPECIFIED, port)),
+            conditions: NetConditions::default(),
+        }
+    }
+
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut role = None;
+        let mut port = 0;
+        let mut conditions = NetConditions::default();
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            let mut value = || args.next().ok_or(format!("{flag} потребує значення"));
+            match flag.as_str() {
+                "--host" => {
+                    port = value()?.parse().map_err(|_| "--host: некоректний порт".to_string())?;
+                    role = Some(NetRole::Host);
+                }
+                "--join" => {
+                    let address = value()?.parse().map_err(|_| "--join: очікується адреса:порт".to_string())?;
+                    role = Some(NetRole::Join(address));
+                }
+                "--port" => {
+                    port = value()?.parse().map_err(|_| "--port: некоректний порт".to_string())?;
+                }
+                "--loss" => {
+                    conditions.loss = value()?.parse().map_err(|_| "--loss: очікується число від 0 до 1".to_string())?;
+                }
+                "--latency" => {
+                    let ms: u64 = value()?.parse().map_err(|_| "--latency: очікується кількість мс".to_string())?;
+                    conditions.latency = Duration::from_millis(ms);
+                }
+                _ => return Err(format!("невідомий параметр {flag}")),
+            }
+        }
+        Ok(role.map(|role| Self {
+            role,
+            bind: SocketAddr::from((Ipv4Addr::UNSPECIFIED, port)),
+            conditions,
+        }))
+    }
+}
+
+#[derive(Resource)]
+pub struct NetSession {
+    pub role: NetRole,
+    pub rollback: Option<Rollback>,
+    transport: UdpTransport,
+    peer: Option<SocketAddr>,
+    seed: u64,
+    rules: SimRules,
+    last_hello: Option<Instant>,
+    last_heard: Instant,
+}
+
+impl NetSession {
+    pub fn start(config: &NetConfig, rules: SimRules) -> io::Result<Self> {
+        let transport = UdpTransport::bind(config.bind, config.conditions)?;
+        let peer = match config.role {
+            NetRole::Host => None,
+            NetRole::Join(address) => Some(address),
+        };
+        Ok(Self {
+            role: config.role,
+            rollback: None,
+            transport,
+            peer,
+            seed: random(),
+            rules,
+            last_hello: None,
+            last_heard: Instant::now(),
+        })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.transport.local_addr()
+    }
+
+    pub fn offer_rules(&mut self, rules: SimRules) {
+        if self.rollback.is_none() {
+            self.rules = rules;
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.rollback.is_some()
+    }
+
+    pub fn timed_out(&self) -> bool {
+        self.is_running() && self.last_heard.elapsed() > PEER_TIMEOUT
+    }
+
+    pub fn poll(&mut self) {
+        if let (NetRole::Join(address), None) = (self.role, &self.rollback)
+            && self.last_hello.is_none_or(|t| t.elapsed() > HELLO_INTERVAL) {
+            self.transport.send(&Packet::Hello, address);
+            self.last_hello = Some(Instant::now());
+        }
+
+        for (packet, from) in self.transport.receive() {
+            match packet {
+                Packet::Hello if self.role == NetRole::Host => {
+                    if self.peer.is_none() {
+                        self.peer = Some(from);
+                        self.rollback = Some(Rollback::new(0, self.seed, self.rules.clone()));
+                    }
+                    if self.peer == Some(from) {
+                        self.last_heard = Instant::now();
+                        self.transport.send(&Packet::Welcome { seed: self.seed, rules: self.rules.clone() }, from);
+                    }
+                }
+                Packet::Welcome { seed, rules } if self.peer == Some(from) => {
+                    self.last_heard = Instant::now();
+                    if self.rollback.is_none() {
+                        self.rules = rules.clone();
+                        self.rollback = Some(Rollback::new(1, seed, rules));
+                    }
+                }
+                Packet::Inputs { received_until, start, inputs } if self.peer == Some(from) => {
+                    self.last_heard = Instant::now();
+                    if let Some(rollback) = self.rollback.as_mut() {
+                        rollback.acknowledge_local(received_until);
+                        for (i, input) in inputs.into_iter().enumerate() {
+                            rollback.add_remote_input(start + i as u32, input);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(rollback) = self.rollback.as_mut() {
+            rollback.apply_rollback();
+        }
+        self.transport.flush();
+    }
+
+    pub fn tick(&mut self, input: NetInput) -> bool {
+        self.poll();
+        let Some(rollback) = self.rollback.as_mut() else {
+            return false;
+        };
+
+        let advanced = rollback.can_advance();
+        if advanced {
+            rollback.add_local_input(input);
+            rollback.advance();
+        }
+        self.send_inputs();
+        advanced
+    }
+
+    pub fn send_inputs(&mut self) {
+        let (Some(rollback), Some(peer)) = (self.rollback.as_ref(), self.peer) else {
+            return;
+        };
+        let pending = rollback.unacknowledged_local_inputs();
+        let start = pending.first().map_or(rollback.frame(), |(frame, _)| *frame);
+        let packet = Packet::Inputs {
+            received_until: rollback.remote_received_until(),
+            start,
+            inputs: pending.iter().take(MAX_INPUTS_PER_PACKET).map(|(_, input)| *input).collect(),
+        };
+        self.transport.send(&packet, peer);
+        self.transport.flush();
+    }
+}
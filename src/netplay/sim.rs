@@ -0,0 +1,458 @@
+use bevy::math::Vec2;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use serde::{Deserialize, Serialize};
+use crate::difficulty::{Difficulty, DifficultyModifiers};
+use crate::levels::*;
+use crate::physics::Collider;
+use crate::stats::{ENEMY_POINTS, STARTING_LIVES};
+use crate::player::{PLAYER_BORDER, PLAYER_STEP, REVIVE_DISTANCE, REVIVE_SECONDS};
+use crate::tuning::GameTuning;
+use crate::waves::{ENTRY_SPEED, Wave, WaveDirector, scatter_waves};
+
+pub const SIM_TICK_RATE: u32 = 60;
+pub const SIM_DT: f32 = 1.0 / SIM_TICK_RATE as f32;
+pub const NET_PLAYERS: usize = 2;
+
+const PLAYER_Y: f32 = -370.0;
+const ARENA_HEIGHT: f32 = 600.0;
+const LAST_LEVEL: u32 = 3;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct SimRules {
+    pub player_laser_speed: f32,
+    pub enemy_laser_speed: f32,
+    pub shoot_frames: u32,
+    pub level_waves: [Vec<usize>; 3],
+    pub layouts: [Option<Vec<Wave>>; 3],
+    pub enemy_spacing: [f32; 3],
+    pub enemy_health: Vec<(EnemyKind, u32)>,
+    pub lives: u32,
+    pub enemy_points: u32,
+}
+
+impl SimRules {
+    pub fn new(tuning: &GameTuning, modifiers: DifficultyModifiers) -> Self {
+        Self {
+            player_laser_speed: tuning.player_laser_speed,
+            enemy_laser_speed: modifiers.laser_speed(tuning),
+            shoot_frames: (modifiers.shoot_interval(tuning) * SIM_TICK_RATE as f32).round() as u32,
+            level_waves: LevelState::ALL.map(|level| modifiers.scale_waves(tuning.waves(level))),
+            layouts: [None, None, None],
+            enemy_spacing: tuning.enemy_spacing,
+            enemy_health: Vec::new(),
+            lives: STARTING_LIVES * modifiers.lives,
+            enemy_points: (ENEMY_POINTS as f32 * modifiers.score).round() as u32,
+        }
+    }
+
+    pub fn with_layouts(mut self, layouts: [Option<Vec<Wave>>; 3]) -> Self {
+        self.layouts = layouts;
+        self
+    }
+
+    pub fn with_enemy_definitions(mut self, definitions: &EnemyDefinitions) -> Self {
+        self.enemy_health = [EnemyKind::Fighter, EnemyKind::Armored].into_iter()
+            .filter_map(|kind| Some((kind, definitions.0.get(&kind)?.health?)))
+            .collect();
+        self
+    }
+
+    pub fn enemy_health(&self, kind: EnemyKind) -> u32 {
+        self.enemy_health.iter().find(|(k, _)| *k == kind).map_or(kind.health(), |(_, health)| *health)
+    }
+
+    pub fn level_waves(&self, level: LevelState, rng: &mut StdRng) -> Vec<Wave> {
+        let index = level.number() as usize - 1;
+        self.layouts[index].clone()
+            .unwrap_or_else(|| scatter_waves(rng, &self.level_waves[index], level.enemy_heights()))
+    }
+}
+
+impl Default for SimRules {
+    fn default() -> Self {
+        Self::new(&GameTuning::default(), Difficulty::default().modifiers())
+    }
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Eq, Debug)]
+pub struct NetInput(pub u8);
+
+impl NetInput {
+    const LEFT: u8 = 1;
+    const RIGHT: u8 = 2;
+    const FIRE: u8 = 4;
+
+    pub fn new(left: bool, right: bool, fire: bool) -> Self {
+        let mut bits = 0;
+        if left { bits |= Self::LEFT; }
+        if right { bits |= Self::RIGHT; }
+        if fire { bits |= Self::FIRE; }
+        Self(bits)
+    }
+
+    pub fn left(self) -> bool { self.0 & Self::LEFT != 0 }
+    pub fn right(self) -> bool { self.0 & Self::RIGHT != 0 }
+    pub fn fire(self) -> bool { self.0 & Self::FIRE != 0 }
+
+    pub fn merge(self, other: NetInput) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct SimRng(pub u64);
+
+impl SimRng {
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    pub fn range(&mut self, min: f32, max: f32) -> f32 {
+        let unit = (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32;
+        min + (max - min) * unit
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SimPlayer {
+    pub x: f32,
+    pub lives: u32,
+    pub score: u32,
+    pub downed: bool,
+    pub revive_frames: u32,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SimEnemy {
+    pub id: u32,
+    pub wave: usize,
+    pub pos: Vec2,
+    pub origin: Vec2,
+    pub entering: bool,
+    pub health: u32,
+    pub movement: EnemyMovement,
+    pub fire: EnemyFire,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SimLaser {
+    pub id: u32,
+    pub owner: usize,
+    pub pos: Vec2,
+    pub prev: Vec2,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SimOutcome {
+    Running,
+    Won,
+    Lost,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct SimState {
+    pub rules: SimRules,
+    pub frame: u32,
+    pub level: u32,
+    pub rng: SimRng,
+    pub next_id: u32,
+    pub players: [SimPlayer; NET_PLAYERS],
+    pub director: WaveDirector,
+    pub enemies: Vec<SimEnemy>,
+    pub player_lasers: Vec<SimLaser>,
+    pub enemy_lasers: Vec<SimLaser>,
+    pub shoot_frames: u32,
+    pub volley: u32,
+    pub outcome: SimOutcome,
+}
+
+impl SimState {
+    pub fn new(seed: u64, rules: SimRules) -> Self {
+        let player = |x| SimPlayer { x, lives: rules.lives, score: 0, downed: false, revive_frames: 0 };
+        let mut state = Self {
+            players: [player(-150.0), player(150.0)],
+            rules,
+            frame: 0,
+            level: 1,
+            rng: SimRng(seed),
+            next_id: NET_PLAYERS as u32,
+            director: WaveDirector::default(),
+            enemies: Vec::new(),
+            player_lasers: Vec::new(),
+            enemy_lasers: Vec::new(),
+            shoot_frames: 0,
+            volley: 0,
+            outcome: SimOutcome::Running,
+        };
+        state.spawn_level();
+        state
+    }
+
+    fn next_id(&mut self) -> u32 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn level_state(&self) -> LevelState {
+        LevelState::ALL[(self.level.clamp(1, LAST_LEVEL) - 1) as usize]
+    }
+
+    fn spawn_level(&mut self) {
+        self.enemies.clear();
+        self.player_lasers.clear();
+        self.enemy_lasers.clear();
+        self.shoot_frames = 0;
+        let level = self.level_state();
+        let mut rng = StdRng::seed_from_u64(self.rng.next_u64());
+        let waves = self.rules.level_waves(level, &mut rng);
+        self.director.begin(level, waves);
+        self.release_enemies(0.0);
+    }
+
+    fn release_enemies(&mut self, delta: f32) {
+        let mut alive = vec![0; self.director.waves.len()];
+        for enemy in &self.enemies {
+            if let Some(count) = alive.get_mut(enemy.wave) {
+                *count += 1;
+            }
+        }
+        for (wave, spawn, entry) in self.director.tick(delta, &alive) {
+            let origin = Vec2::new(spawn.x, spawn.y);
+            let pos = entry.start(origin);
+            let id = self.next_id();
+            self.enemies.push(SimEnemy {
+                id,
+                wave,
+                pos,
+                origin,
+                entering: pos != origin,
+                health: self.rules.enemy_health(spawn.kind),
+                movement: spawn.movement,
+                fire: spawn.fire,
+            });
+        }
+    }
+
+    fn move_enemies(&mut self) {
+        let step = ENTRY_SPEED * SIM_DT;
+        let elapsed = self.frame as f32 * SIM_DT;
+        for enemy in self.enemies.iter_mut() {
+            if enemy.entering {
+                if enemy.pos.distance(enemy.origin) <= step {
+                    enemy.pos = enemy.origin;
+                    enemy.entering = false;
+                } else {
+                    enemy.pos += (enemy.origin - enemy.pos).normalize() * step;
+                }
+                continue;
+            }
+            match enemy.movement {
+                EnemyMovement::Static => {}
+                EnemyMovement::Sway => {
+                    enemy.pos.x = enemy.origin.x + SWAY_AMPLITUDE * (elapsed * 2.0 + enemy.origin.y * 0.01).sin();
+                }
+                EnemyMovement::Descend => {
+                    enemy.pos.y = (enemy.pos.y - DESCEND_SPEED * SIM_DT).max(DESCEND_FLOOR);
+                }
+            }
+        }
+    }
+
+    pub fn step(&mut self, inputs: [NetInput; NET_PLAYERS]) {
+        self.frame += 1;
+        if self.outcome != SimOutcome::Running {
+            return;
+        }
+
+        for (i, input) in inputs.into_iter().enumerate() {
+            if self.players[i].downed {
+                continue;
+            }
+            if input.right() {
+                self.players[i].x += PLAYER_STEP;
+            }
+            if input.left() {
+                self.players[i].x -= PLAYER_STEP;
+            }
+            self.players[i].x = self.players[i].x.clamp(-PLAYER_BORDER, PLAYER_BORDER);
+            if input.fire() {
+                let pos = Vec2::new(self.players[i].x, PLAYER_Y);
+                let id = self.next_id();
+                self.player_lasers.push(SimLaser { id, owner: i, pos, prev: pos });
+            }
+        }
+
+        self.release_enemies(SIM_DT);
+        self.move_enemies();
+        self.separate_enemies();
+
+        self.shoot_frames += 1;
+        if self.shoot_frames >= self.rules.shoot_frames {
+            self.shoot_frames = 0;
+            self.volley += 1;
+            let volley = self.volley;
+            let shooters: Vec<Vec2> = self.enemies.iter()
+                .filter(|e| !e.entering && e.fire.fires_on(volley))
+                .map(|e| e.pos)
+                .collect();
+            for pos in shooters {
+                let id = self.next_id();
+                self.enemy_lasers.push(SimLaser { id, owner: 0, pos, prev: pos });
+            }
+        }
+
+        for laser in self.player_lasers.iter_mut() {
+            laser.prev = laser.pos;
+            laser.pos.y += self.rules.player_laser_speed * SIM_DT;
+        }
+        for laser in self.enemy_lasers.iter_mut() {
+            laser.prev = laser.pos;
+            laser.pos.y -= self.rules.enemy_laser_speed * SIM_DT;
+        }
+
+        self.collide_lasers_with_enemies();
+        self.collide_lasers_with_lasers();
+        self.collide_lasers_with_players();
+        self.revive_players();
+
+        self.player_lasers.retain(|l| l.pos.y.abs() < ARENA_HEIGHT);
+        self.enemy_lasers.retain(|l| l.pos.y.abs() < ARENA_HEIGHT);
+
+        if self.players.iter().all(|p| p.downed) {
+            self.outcome = SimOutcome::Lost;
+        } else if self.director.is_cleared(self.enemies.len()) {
+            if self.level == LAST_LEVEL {
+                self.outcome = SimOutcome::Won;
+            } else {
+                self.level += 1;
+                self.spawn_level();
+            }
+        }
+    }
+
+    fn separate_enemies(&mut self) {
+        let minimum_distance = self.rules.enemy_spacing[self.level_state().number() as usize - 1];
+        let positions: Vec<Vec2> = self.enemies.iter().map(|e| e.pos).collect();
+        for (i, enemy) in self.enemies.iter_mut().enumerate() {
+            for (j, other) in positions.iter().enumerate() {
+                if i != j {
+                    let d = enemy.pos - *other;
+                    let dist = d.length();
+                    if dist > 0.0 && dist < minimum_distance {
+                        enemy.pos += d.normalize() * (minimum_distance - dist);
+                    }
+                }
+            }
+        }
+    }
+
+    fn collide_lasers_with_enemies(&mut self) {
+        let collider = enemy_collider();
+        let mut i = 0;
+        while i < self.player_lasers.len() {
+            let laser = &self.player_lasers[i];
+            let hit = self.enemies.iter()
+                .position(|e| collider.swept(e.pos, laser.prev, laser.pos));
+            match hit {
+                Some(enemy) => {
+                    let owner = laser.owner;
+                    self.player_lasers.remove(i);
+                    if self.enemies[enemy].health > 1 {
+                        self.enemies[enemy].health -= 1;
+                    } else {
+                        self.enemies.remove(enemy);
+                        self.players[owner].score += self.rules.enemy_points;
+                    }
+                }
+                None => i += 1,
+            }
+        }
+    }
+
+    fn collide_lasers_with_lasers(&mut self) {
+        let collider = Collider::new(Vec2::splat(10.0));
+        let mut i = 0;
+        while i < self.player_lasers.len() {
+            let laser = &self.player_lasers[i];
+            let hit = self.enemy_lasers.iter()
+                .position(|e| collider.swept(Vec2::ZERO, laser.prev - e.prev, laser.pos - e.pos));
+            match hit {
+                Some(enemy_laser) => {
+                    self.enemy_lasers.remove(enemy_laser);
+                    self.player_lasers.remove(i);
+                }
+                None => i += 1,
+            }
+        }
+    }
+
+    fn collide_lasers_with_players(&mut self) {
+        let collider = Collider::new(Vec2::splat(20.0));
+        for player in self.players.iter_mut() {
+            if player.downed {
+                continue;
+            }
+            let center = Vec2::new(player.x, PLAYER_Y);
+            if let Some(hit) = self.enemy_lasers.iter().position(|l| collider.swept(center, l.prev, l.pos)) {
+                self.enemy_lasers.remove(hit);
+                player.lives = player.lives.saturating_sub(1);
+                if player.lives == 0 {
+                    player.downed = true;
+                    player.revive_frames = 0;
+                }
+            }
+        }
+    }
+
+    fn revive_players(&mut self) {
+        let revive_frames = (REVIVE_SECONDS * SIM_TICK_RATE as f32) as u32;
+        let standing: Vec<f32> = self.players.iter().filter(|p| !p.downed).map(|p| p.x).collect();
+        for player in self.players.iter_mut().filter(|p| p.downed) {
+            if standing.iter().any(|x| (x - player.x).abs() < REVIVE_DISTANCE) {
+                player.revive_frames += 1;
+                if player.revive_frames >= revive_frames {
+                    player.downed = false;
+                    player.lives = 1;
+                }
+            } else {
+                player.revive_frames = 0;
+            }
+        }
+    }
+
+    pub fn checksum(&self) -> u64 {
+        let mut hash = 0xCBF2_9CE4_8422_2325_u64;
+        let mut mix = |value: u64| {
+            hash ^= value;
+            hash = hash.wrapping_mul(0x0100_0000_01B3);
+        };
+        mix(self.frame as u64);
+        mix(self.level as u64);
+        mix(self.rng.0);
+        mix(self.director.next_wave as u64);
+        mix(self.director.elapsed.to_bits() as u64);
+        for p in &self.players {
+            mix(p.x.to_bits() as u64);
+            mix(p.lives as u64);
+            mix(p.score as u64);
+            mix(p.downed as u64);
+        }
+        for e in &self.enemies {
+            mix(e.id as u64);
+            mix(e.health as u64);
+            mix(e.pos.x.to_bits() as u64);
+            mix(e.pos.y.to_bits() as u64);
+        }
+        for l in self.player_lasers.iter().chain(self.enemy_lasers.iter()) {
+            mix(l.id as u64);
+            mix(l.pos.x.to_bits() as u64);
+            mix(l.pos.y.to_bits() as u64);
+        }
+        hash
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::VecDeque;
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+use super::sim::{NetInput, SimRng, SimRules};
+
+const MAGIC: u8 = 0x53;
+const MAX_PACKET: usize = 16 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct NetConditions {
+    pub loss: f32,
+    pub latency: Duration,
+}
+
+impl NetConditions {
+    pub fn presets() -> [NetConditions; 3] {
+        [
+            NetConditions::default(),
+            NetConditions { loss: 0.1, latency: Duration::from_millis(60) },
+            NetConditions { loss: 0.25, latency: Duration::from_millis(150) },
+        ]
+    }
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Packet {
+    Hello,
+    Welcome { seed: u64, rules: SimRules },
+    Inputs { received_until: u32, start: u32, inputs: Vec<NetInput> },
+}
+
+impl Packet {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![MAGIC];
+        match self {
+            Packet::Hello => bytes.push(0),
+            Packet::Welcome { seed, rules } => {
+                bytes.push(1);
+                bytes.extend_from_slice(&seed.to_le_bytes());
+                bytes.extend(ron::ser::to_string(rules).unwrap_or_default().into_bytes());
+            }
+            Packet::Inputs { received_until, start, inputs } => {
+                bytes.push(2);
+                bytes.extend_from_slice(&received_until.to_le_bytes());
+                bytes.extend_from_slice(&start.to_le_bytes());
+                bytes.extend(inputs.iter().map(|i| i.0));
+            }
+        }
+        bytes
+    }
+
+    pub fn decode(bytes: &[u8]) -> Option<Packet> {
+        let (&magic, rest) = bytes.split_first()?;
+        if magic != MAGIC {
+            return None;
+        }
+        let (&tag, body) = rest.split_first()?;
+        let u32_at = |at: usize| -> Option<u32> {
+            Some(u32::from_le_bytes(body.get(at..at + 4)?.try_into().ok()?))
+        };
+        match tag {
+            0 => Some(Packet::Hello),
+            1 => Some(Packet::Welcome {
+                seed: u64::from_le_bytes(body.get(0..8)?.try_into().ok()?),
+                rules: ron::de::from_bytes(body.get(8..)?).ok()?,
+            }),
+            2 => Some(Packet::Inputs {
+                received_until: u32_at(0)?,
+                start: u32_at(4)?,
+                inputs: body.get(8..)?.iter().map(|b| NetInput(*b)).collect(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+pub struct UdpTransport {
+    socket: UdpSocket,
+    conditions: NetConditions,
+    delayed: VecDeque<(Instant, SocketAddr, Vec<u8>)>,
+    rng: SimRng,
+}
+
+impl UdpTransport {
+    pub fn bind(addr: SocketAddr, conditions: NetConditions) -> io::Result<Self> {
+        let socket = UdpSocket::bind(addr)?;
+        socket.set_nonblocking(true)?;
+        let rng = SimRng(socket.local_addr()?.port() as u64);
+        Ok(Self { socket, conditions, delayed: VecDeque::new(), rng })
+    }
+
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    pub fn send(&mut self, packet: &Packet, to: SocketAddr) {
+        if self.conditions.loss > 0.0 && self.rng.range(0.0, 1.0) < self.conditions.loss {
+            return;
+        }
+        let bytes = packet.encode();
+        if self.conditions.latency.is_zero() {
+            let _ = self.socket.send_to(&bytes, to);
+        } else {
+            self.delayed.push_back((Instant::now() + self.conditions.latency, to, bytes));
+        }
+    }
+
+    pub fn flush(&mut self) {
+        let now = Instant::now();
+        while let Some((due, to, bytes)) = self.delayed.front() {
+            if *due > now {
+                break;
+            }
+            let _ = self.socket.send_to(bytes, *to);
+            self.delayed.pop_front();
+        }
+    }
+
+    pub fn receive(&mut self) -> Vec<(Packet, SocketAddr)> {
+        let mut packets = Vec::new();
+        let mut buffer = [0u8; MAX_PACKET];
+        while let Ok((len, from)) = self.socket.recv_from(&mut buffer) {
+            if let Some(packet) = Packet::decode(&buffer[..len]) {
+                packets.push((packet, from));
+            }
+        }
+        packets
+    }
+}
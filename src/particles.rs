@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+use rand::{thread_rng, Rng};
+use crate::game::{GameSet, GameState, GameplayObject, InRun};
+
+#[derive(Component, Clone)]
+pub struct ParticleEmitter {
+    pub rate: f32,
+    pub burst: u32,
+    pub lifetime: f32,
+    pub direction: Vec2,
+    pub spread: f32,
+    pub speed_min: f32,
+    pub speed_max: f32,
+    pub color_start: Color,
+    pub color_end: Color,
+    pub size_start: f32,
+    pub size_end: f32,
+    pub accumulator: f32,
+}
+
+#[derive(Component)]
+pub struct Particle {
+    pub velocity: Vec2,
+    pub age: f32,
+    pub lifetime: f32,
+    pub color_start: Color,
+    pub color_end: Color,
+    pub size_start: f32,
+    pub size_end: f32,
+}
+
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Update, (emit_particles, update_particles)
+            .in_set(GameSet::Presentation)
+            .run_if(in_state(GameState::InGame)));
+    }
+}
+
+impl ParticleEmitter {
+    pub fn engine_trail() -> Self {
+        Self {
+            rate: 60.0,
+            burst: 0,
+            lifetime: 0.35,
+            direction: Vec2::NEG_Y,
+            spread: 0.35,
+            speed_min: 60.0,
+            speed_max: 120.0,
+            color_start: Color::srgba(1.0, 0.85, 0.4, 0.9),
+            color_end: Color::srgba(0.9, 0.2, 0.1, 0.0),
+            size_start: 5.0,
+            size_end: 1.0,
+            accumulator: 0.0,
+        }
+    }
+
+    pub fn laser_impact() -> Self {
+        Self {
+            rate: 0.0,
+            burst: 10,
+            lifetime: 0.25,
+            direction: Vec2::Y,
+            spread: std::f32::consts::PI,
+            speed_min: 80.0,
+            speed_max: 200.0,
+            color_start: Color::srgba(0.6, 1.0, 0.6, 1.0),
+            color_end: Color::srgba(0.1, 0.8, 0.2, 0.0),
+            size_start: 3.0,
+            size_end: 1.0,
+            accumulator: 0.0,
+        }
+    }
+
+    pub fn explosion() -> Self {
+        Self {
+            rate: 0.0,
+            burst: 40,
+            lifetime: 0.7,
+            direction: Vec2::Y,
+            spread: std::f32::consts::PI,
+            speed_min: 40.0,
+            speed_max: 260.0,
+            color_start: Color::srgba(1.0, 0.9, 0.5, 1.0),
+            color_end: Color::srgba(0.4, 0.1, 0.05, 0.0),
+            size_start: 6.0,
+            size_end: 2.0,
+            accumulator: 0.0,
+        }
+    }
+
+    pub fn sparks() -> Self {
+        Self {
+            rate: 0.0,
+            burst: 14,
+            lifetime: 0.3,
+            direction: Vec2::X,
+            spread: std::f32::consts::PI,
+            speed_min: 150.0,
+            speed_max: 320.0,
+            color_start: Color::srgba(1.0, 1.0, 0.8, 1.0),
+            color_end: Color::srgba(1.0, 0.4, 0.2, 0.0),
+            size_start: 2.5,
+            size_end: 0.5,
+            accumulator: 0.0,
+        }
+    }
+
+    fn particle(&self, rng: &mut impl Rng) -> Particle {
+        let angle = rng.gen_range(-self.spread..=self.spread);
+        let speed = rng.gen_range(self.speed_min..=self.speed_max);
+        Particle {
+            velocity: Vec2::from_angle(angle).rotate(self.direction) * speed,
+            age: 0.0,
+            lifetime: self.lifetime,
+            color_start: self.color_start,
+            color_end: self.color_end,
+            size_start: self.size_start,
+            size_end: self.size_end,
+        }
+    }
+}
+
+pub fn spawn_particle_burst(commands: &mut Commands, position: Vec3, emitter: ParticleEmitter) {
+    commands.spawn((
+        emitter,
+        Transform::from_translation(position),
+        GlobalTransform::from_translation(position),
+        DespawnOnExit(InRun),
+    ));
+}
+
+pub fn emit_particles(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut emitters: Query<(Entity, &mut ParticleEmitter, &GlobalTransform)>,
+) {
+    let mut rng = thread_rng();
+    for (entity, mut emitter, transform) in emitters.iter_mut() {
+        let position = transform.translation();
+        let count = if emitter.burst > 0 {
+            commands.entity(entity).despawn();
+            emitter.burst
+        } else {
+            emitter.accumulator += emitter.rate * time.delta_secs();
+            let count = emitter.accumulator.floor();
+            emitter.accumulator -= count;
+            count as u32
+        };
+
+        for _ in 0..count {
+            let particle = emitter.particle(&mut rng);
+            commands.spawn((
+                Sprite {
+                    color: particle.color_start,
+                    custom_size: Some(Vec2::splat(particle.size_start)),
+                    ..default()
+                },
+                Transform::from_xyz(position.x, position.y, 0.4),
+                particle,
+                GameplayObject,
+                DespawnOnExit(InRun)
+            ));
+        }
+    }
+}
+
+pub fn update_particles(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut particles: Query<(Entity, &mut Particle, &mut Transform, &mut Sprite)>,
+) {
+    let dt = time.delta_secs();
+    for (entity, mut particle, mut transform, mut sprite) in particles.iter_mut() {
+        particle.age += dt;
+        if particle.age >= particle.lifetime {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        let t = particle.age / particle.lifetime;
+        transform.translation += (particle.velocity * dt).extend(0.0);
+        sprite.color = particle.color_start.mix(&particle.color_end, t);
+        sprite.custom_size = Some(Vec2::splat(particle.size_start.lerp(particle.size_end, t)));
+    }
+}
@@ -0,0 +1,101 @@
+use bevy::prelude::*;
+
+pub const DEFAULT_TICK_RATE: f64 = 60.0;
+
+#[derive(Resource)]
+pub struct TickRate(pub f64);
+
+impl Default for TickRate {
+    fn default() -> Self {
+        Self(DEFAULT_TICK_RATE)
+    }
+}
+
+#[derive(Component, Default, Clone, Copy)]
+pub struct PhysicalTranslation(pub Vec2);
+
+#[derive(Component, Default, Clone, Copy)]
+pub struct PreviousPhysicalTranslation(pub Vec2);
+
+#[derive(Component, Clone, Copy)]
+pub struct Collider {
+    pub half_size: Vec2,
+    pub offset: Vec2,
+}
+
+impl Collider {
+    pub fn new(half_size: Vec2) -> Self {
+        Self { half_size, offset: Vec2::ZERO }
+    }
+
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    pub fn contains(&self, center: Vec2, point: Vec2) -> bool {
+        let d = (point - center - self.offset).abs();
+        d.x < self.half_size.x && d.y < self.half_size.y
+    }
+
+    pub fn swept(&self, center: Vec2, start: Vec2, end: Vec2) -> bool {
+        segment_hits_box(start, end, center + self.offset, self.half_size)
+    }
+}
+
+pub fn physical_translation(position: Vec2) -> (PhysicalTranslation, PreviousPhysicalTranslation) {
+    (PhysicalTranslation(position), PreviousPhysicalTranslation(position))
+}
+
+pub fn segment_hits_box(start: Vec2, end: Vec2, center: Vec2, half_size: Vec2) -> bool {
+    let delta = end - start;
+    let mut t_min = 0.0_f32;
+    let mut t_max = 1.0_f32;
+    for axis in 0..2 {
+        let (s, d, c, h) = (start[axis], delta[axis], center[axis], half_size[axis]);
+        if d.abs() < f32::EPSILON {
+            if (s - c).abs() >= h {
+                return false;
+            }
+            continue;
+        }
+        let t1 = (c - h - s) / d;
+        let t2 = (c + h - s) / d;
+        t_min = t_min.max(t1.min(t2));
+        t_max = t_max.min(t1.max(t2));
+        if t_min > t_max {
+            return false;
+        }
+    }
+    true
+}
+
+pub struct PhysicsPlugin;
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<TickRate>()
+            .add_systems(PreUpdate, apply_tick_rate)
+            .add_systems(RunFixedMainLoop, interpolate_rendered_transforms
+                .in_set(RunFixedMainLoopSystems::AfterFixedMainLoop));
+    }
+}
+
+pub fn apply_tick_rate(tick_rate: Res<TickRate>, mut fixed_time: ResMut<Time<Fixed>>) {
+    if tick_rate.is_changed() {
+        fixed_time.set_timestep_hz(tick_rate.0);
+    }
+}
+
+pub fn interpolate_rendered_transforms(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(&mut Transform, &PhysicalTranslation, &PreviousPhysicalTranslation)>,
+) {
+    let alpha = fixed_time.overstep_fraction();
+    for (mut transform, current, previous) in query.iter_mut() {
+        let rendered = previous.0.lerp(current.0, alpha);
+        transform.translation.x = rendered.x;
+        transform.translation.y = rendered.y;
+    }
+}
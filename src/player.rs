@@ -1,41 +1,273 @@
 use bevy::prelude::*;
-use crate::game::GameplayObject;
+use crate::animation::*;
+use crate::bot::BotBrain;
+use crate::console::*;
+use crate::difficulty::Difficulty;
+use crate::game::{GameMode, GameSet, GameplayObject, InRun};
+use crate::particles::ParticleEmitter;
+use crate::physics::Collider;
+use crate::stats::*;
+use crate::versus::VERSUS_LIVES;
 
 #[derive(Component)]
 pub struct PlayerStruct;
 
-pub fn load_players(mut commands: Commands, asset_server: Res<AssetServer>) {
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct PlayerId(pub usize);
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PlayerSide {
+    Bottom,
+    Top,
+}
+
+#[derive(Component, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ControlScheme {
+    Keyboard,
+    KeyboardLeft,
+    KeyboardRight,
+    Gamepad(Entity),
+    Bot,
+    Agent,
+}
+
+#[derive(Component, Default, Clone, Copy)]
+pub struct PlayerInput {
+    pub left: bool,
+    pub right: bool,
+    pub fire: bool,
+}
+
+#[derive(Component)]
+pub struct Downed {
+    pub revive: Timer,
+}
+
+impl Default for Downed {
+    fn default() -> Self {
+        Self { revive: Timer::from_seconds(REVIVE_SECONDS, TimerMode::Once) }
+    }
+}
+
+pub type ActivePlayer = (With<PlayerStruct>, Without<Downed>);
+
+pub const PLAYER_STEP: f32 = 25.0;
+pub const PLAYER_BORDER: f32 = 475.0;
+pub const REVIVE_SECONDS: f32 = 3.0;
+pub const REVIVE_DISTANCE: f32 = 60.0;
+
+pub const PLAYER_COLORS: [Color; 2] = [
+    Color::WHITE,
+    Color::srgb(0.45, 0.8, 1.0),
+];
+
+pub fn player_color(id: PlayerId) -> Color {
+    PLAYER_COLORS[id.0 % PLAYER_COLORS.len()]
+}
+
+pub struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Update, read_player_input.in_set(GameSet::Input))
+            .add_systems(FixedUpdate, (keys_for_players, borders_for_player)
+                .chain()
+                .in_set(GameSet::Movement))
+            .add_systems(FixedUpdate, clear_player_input
+                .in_set(GameSet::Movement)
+                .after(borders_for_player))
+            .add_systems(FixedUpdate, revive_downed_players
+                .in_set(GameSet::Resolution)
+                .run_if(not(resource_equals(GameMode::Versus))))
+            .add_systems(Update, tint_players.in_set(GameSet::Presentation))
+            .add_console_command(ConsoleCommand::new("teleport", "teleport <x> <y> [гравець] — перемістити гравця", teleport_command).cheat());
+    }
+}
+
+pub fn teleport_command(world: &mut World, args: &[&str]) -> Result<String, String> {
+    let x: f32 = argument(args, 0, "x")?;
+    let y: f32 = argument(args, 1, "y")?;
+    let id = match args.get(2) {
+        Some(_) => argument::<usize>(args, 2, "гравець")?.saturating_sub(1),
+        None => 0,
+    };
+    let mut players = world.query_filtered::<(&PlayerId, &mut Transform), With<PlayerStruct>>();
+    let Some((_, mut transform)) = players.iter_mut(world).find(|(player, _)| player.0 == id) else {
+        return Err(format!("гравця {} немає", id + 1));
+    };
+    transform.translation.x = x;
+    transform.translation.y = y;
+    Ok(format!("Гравець {} у ({x}, {y})", id + 1))
+}
+
+pub fn control_schemes(mode: GameMode, gamepads: &[Entity]) -> Vec<ControlScheme> {
+    match mode {
+        GameMode::Single => vec![ControlScheme::Keyboard],
+        GameMode::Bot => vec![ControlScheme::Bot],
+        GameMode::Agent => vec![ControlScheme::Agent],
+        GameMode::Coop | GameMode::Versus if gamepads.len() >= 2 => vec![
+            ControlScheme::Gamepad(gamepads[0]),
+            ControlScheme::Gamepad(gamepads[1]),
+        ],
+        GameMode::Coop | GameMode::Versus => vec![ControlScheme::KeyboardLeft, ControlScheme::KeyboardRight],
+    }
+}
+
+pub fn spawn_players(commands: &mut Commands, atlases: &AnimationAtlases, mode: GameMode, difficulty: Difficulty, gamepads: &[Entity]) {
+    let schemes = control_schemes(mode, gamepads);
+    if mode == GameMode::Versus {
+        for (i, scheme) in schemes.into_iter().enumerate() {
+            let side = if i == 0 { PlayerSide::Bottom } else { PlayerSide::Top };
+            spawn_player(commands, atlases, PlayerId(i), scheme, side, 0.0, VERSUS_LIVES);
+        }
+        return;
+    }
+    let spacing = 300.0;
+    let first_x = -spacing * (schemes.len() - 1) as f32 / 2.0;
+    for (i, scheme) in schemes.into_iter().enumerate() {
+        let x = first_x + spacing * i as f32;
+        spawn_player(commands, atlases, PlayerId(i), scheme, PlayerSide::Bottom, x, STARTING_LIVES * difficulty.modifiers().lives);
+    }
+}
+
+pub fn side_y(side: PlayerSide) -> f32 {
+    match side {
+        PlayerSide::Bottom => -370.0,
+        PlayerSide::Top => 370.0,
+    }
+}
+
+pub fn spawn_player(
+    commands: &mut Commands,
+    atlases: &AnimationAtlases,
+    id: PlayerId,
+    scheme: ControlScheme,
+    side: PlayerSide,
+    x: f32,
+    lives: u32,
+) {
+    let mut sprite = atlases.player_sprite();
+    sprite.color = player_color(id);
+    sprite.flip_y = side == PlayerSide::Top;
+    let mut trail = ParticleEmitter::engine_trail();
+    let mut trail_y = -20.0;
+    if side == PlayerSide::Top {
+        trail.direction = -trail.direction;
+        trail_y = -trail_y;
+    }
     commands.spawn((
-        Sprite {
-            image: asset_server.load("images/players/player.png"),
-            ..default()
-        },
-        Transform::from_xyz(0.0, -370.0, 0.5),
+        sprite,
+        SpriteAnimation::new(vec![PLAYER_FRAME_NEUTRAL], 0.15, AnimationMode::Once),
+        Transform::from_xyz(x, side_y(side), 0.5),
+        Collider::new(Vec2::splat(20.0)),
         PlayerStruct,
-        GameplayObject
-    ));
+        id,
+        side,
+        scheme,
+        PlayerInput::default(),
+        Lives(lives),
+        PlayerScore::default(),
+        GameplayObject,
+        DespawnOnExit(InRun)
+    )).with_children(|parent| {
+        parent.spawn((
+            trail,
+            Transform::from_xyz(0.0, trail_y, 0.0),
+        ));
+    }).insert_if(BotBrain::default(), || scheme == ControlScheme::Bot);
+}
+
+pub fn read_player_input(
+    key_code: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    mut player_query: Query<(&ControlScheme, &mut PlayerInput)>,
+) {
+    for (scheme, mut input) in player_query.iter_mut() {
+        let pressed = match *scheme {
+            ControlScheme::Keyboard => PlayerInput {
+                left: key_code.just_pressed(KeyCode::KeyA) || key_code.just_pressed(KeyCode::ArrowLeft),
+                right: key_code.just_pressed(KeyCode::KeyD) || key_code.just_pressed(KeyCode::ArrowRight),
+                fire: key_code.just_pressed(KeyCode::Space),
+            },
+            ControlScheme::KeyboardLeft => PlayerInput {
+                left: key_code.just_pressed(KeyCode::KeyA),
+                right: key_code.just_pressed(KeyCode::KeyD),
+                fire: key_code.just_pressed(KeyCode::Space),
+            },
+            ControlScheme::KeyboardRight => PlayerInput {
+                left: key_code.just_pressed(KeyCode::ArrowLeft),
+                right: key_code.just_pressed(KeyCode::ArrowRight),
+                fire: key_code.just_pressed(KeyCode::Enter),
+            },
+            ControlScheme::Gamepad(entity) => match gamepads.get(entity) {
+                Ok(gamepad) => PlayerInput {
+                    left: gamepad.just_pressed(GamepadButton::DPadLeft),
+                    right: gamepad.just_pressed(GamepadButton::DPadRight),
+                    fire: gamepad.just_pressed(GamepadButton::South),
+                },
+                Err(_) => PlayerInput::default(),
+            },
+            ControlScheme::Bot | ControlScheme::Agent => continue,
+        };
+        input.left |= pressed.left;
+        input.right |= pressed.right;
+        input.fire |= pressed.fire;
+    }
 }
 
-pub fn keys_for_players(asset_server: Res<AssetServer>,
-key_code: Res<ButtonInput<KeyCode>>, mut player_query: Query<(&mut Transform, &mut Sprite), With<PlayerStruct>>) {
-    for (mut t, mut texture) in player_query.iter_mut() {
-        if key_code.just_pressed(KeyCode::KeyD) || key_code.just_pressed(KeyCode::ArrowRight) {
-            t.translation.x += 25.0;
-            texture.image = asset_server.load("images/players/playerRight.png");
+pub fn clear_player_input(mut player_query: Query<&mut PlayerInput>) {
+    for mut input in player_query.iter_mut() {
+        *input = PlayerInput::default();
+    }
+}
+
+pub fn keys_for_players(
+    mut player_query: Query<(&PlayerInput, &mut Transform, &mut SpriteAnimation), ActivePlayer>,
+) {
+    for (input, mut t, mut animation) in player_query.iter_mut() {
+        if input.right {
+            t.translation.x += PLAYER_STEP;
+            *animation = bank_animation(PLAYER_FRAME_RIGHT);
         }
-        if key_code.just_pressed(KeyCode::KeyA) || key_code.just_pressed(KeyCode::ArrowLeft) {
-            t.translation.x -= 25.0;
-            texture.image = asset_server.load("images/players/playerLeft.png");
+        if input.left {
+            t.translation.x -= PLAYER_STEP;
+            *animation = bank_animation(PLAYER_FRAME_LEFT);
         }
     }
 }
 
 pub fn borders_for_player(mut player_query: Query<&mut Transform, With<PlayerStruct>>) {
     for mut t in player_query.iter_mut() {
-        if t.translation.x >= 475.0 {
-            t.translation.x = 475.0;
-        } else if t.translation.x <= -475.0 {
-            t.translation.x = -475.0;
+        t.translation.x = t.translation.x.clamp(-PLAYER_BORDER, PLAYER_BORDER);
+    }
+}
+
+pub fn revive_downed_players(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut downed_query: Query<(&Transform, &mut Downed, &mut Lives, Entity), With<PlayerStruct>>,
+    rescuers: Query<&Transform, ActivePlayer>,
+) {
+    for (downed_tr, mut downed, mut lives, entity) in downed_query.iter_mut() {
+        let rescued = rescuers.iter()
+            .any(|t| (t.translation.x - downed_tr.translation.x).abs() < REVIVE_DISTANCE);
+
+        if !rescued {
+            downed.revive.reset();
+            continue;
         }
+
+        if downed.revive.tick(time.delta()).is_finished() {
+            lives.0 = 1;
+            commands.entity(entity).remove::<Downed>();
+        }
+    }
+}
+
+pub fn tint_players(mut player_query: Query<(&PlayerId, &mut Sprite, Has<Downed>), With<PlayerStruct>>) {
+    for (id, mut sprite, downed) in player_query.iter_mut() {
+        let color = player_color(*id);
+        sprite.color = if downed { color.with_alpha(0.3) } else { color };
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,318 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::TcpStream;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::game::{GameMode, GameRng, GameState};
+use crate::headless::headless_app;
+use crate::lasers_enemies::LasersEnemiesStruct;
+use crate::lasers_player::LasersPlayerStruct;
+use crate::levels::*;
+use crate::player::*;
+use crate::stats::*;
+
+pub const DEFAULT_RL_PORT: u16 = 5555;
+pub const GRID_WIDTH: usize = 50;
+pub const GRID_HEIGHT: usize = 40;
+const ARENA_WIDTH: f32 = 1000.0;
+const ARENA_HEIGHT: f32 = 800.0;
+
+pub const CELL_EMPTY: u8 = 0;
+pub const CELL_PLAYER: u8 = 1;
+pub const CELL_ENEMY: u8 = 2;
+pub const CELL_PLAYER_LASER: u8 = 3;
+pub const CELL_ENEMY_LASER: u8 = 4;
+
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ObservationKind {
+    #[default]
+    Positions,
+    Grid,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct RlConfig {
+    pub seed: Option<u64>,
+    pub obs: ObservationKind,
+    pub frame_skip: u32,
+    pub max_steps: u32,
+    pub score_scale: f32,
+    pub life_penalty: f32,
+    pub death_penalty: f32,
+}
+
+impl Default for RlConfig {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            obs: ObservationKind::Positions,
+            frame_skip: 4,
+            max_steps: 10_000,
+            score_scale: 0.01,
+            life_penalty: -1.0,
+            death_penalty: -10.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Default, Debug)]
+#[serde(default)]
+pub struct RlAction {
+    pub left: bool,
+    pub right: bool,
+    pub fire: bool,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Observation {
+    Positions {
+        player: Option<[f32; 2]>,
+        enemies: Vec<[f32; 2]>,
+        player_lasers: Vec<[f32; 2]>,
+        enemy_lasers: Vec<[f32; 2]>,
+    },
+    Grid {
+        width: usize,
+        height: usize,
+        cells: Vec<u8>,
+    },
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct StepInfo {
+    pub score: u32,
+    pub lives: u32,
+    pub level: u32,
+    pub steps: u32,
+    pub won: bool,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct StepResult {
+    pub observation: Observation,
+    pub reward: f32,
+    pub done: bool,
+    pub truncated: bool,
+    pub info: StepInfo,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(tag = "cmd", rename_all = "lowercase")]
+pub enum RlRequest {
+    Reset {
+        #[serde(flatten)]
+        config: RlConfig,
+    },
+    Step {
+        #[serde(default)]
+        action: RlAction,
+    },
+    Spec,
+    Close,
+}
+
+pub struct RlEnv {
+    app: App,
+    config: RlConfig,
+    steps: u32,
+    score: u32,
+    lives: u32,
+}
+
+impl RlEnv {
+    pub fn new(config: RlConfig) -> Self {
+        let mut app = headless_app();
+        if let Some(seed) = config.seed {
+            app.insert_resource(GameRng::seeded(seed));
+        }
+        app.insert_resource(GameMode::Agent);
+        app.update();
+        app.world_mut().resource_mut::<NextState<GameState>>().set(GameState::InGame);
+        app.update();
+
+        let mut env = Self { app, config, steps: 0, score: 0, lives: 0 };
+        env.lives = env.lives();
+        env
+    }
+
+    pub fn reset(config: RlConfig) -> (Self, StepResult) {
+        let mut env = Self::new(config);
+        let result = env.result(0.0);
+        (env, result)
+    }
+
+    fn state(&self) -> GameState {
+        *self.app.world().resource::<State<GameState>>().get()
+    }
+
+    fn lives(&mut self) -> u32 {
+        let world = self.app.world_mut();
+        world.query_filtered::<&Lives, With<PlayerStruct>>().iter(world).map(|l| l.0).sum()
+    }
+
+    fn set_input(&mut self, input: PlayerInput) {
+        let world = self.app.world_mut();
+        for mut player_input in world.query_filtered::<&mut PlayerInput, With<PlayerStruct>>().iter_mut(world) {
+            *player_input = input;
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        matches!(self.state(), GameState::GameOver | GameState::Win)
+    }
+
+    pub fn step(&mut self, action: RlAction) -> StepResult {
+        if self.is_done() {
+            return self.result(0.0);
+        }
+
+        self.set_input(PlayerInput { left: action.left, right: action.right, fire: action.fire });
+        for _ in 0..self.config.frame_skip.max(1) {
+            self.app.update();
+            if self.is_done() {
+                break;
+            }
+        }
+        self.steps += 1;
+
+        let score = self.app.world().resource::<Score>().0;
+        let lives = self.lives();
+        let mut reward = (score.saturating_sub(self.score)) as f32 * self.config.score_scale;
+        if lives < self.lives {
+            reward += self.config.life_penalty * (self.lives - lives) as f32;
+        }
+        if self.state() == GameState::GameOver {
+            reward += self.config.death_penalty;
+        }
+        self.score = score;
+        self.lives = lives;
+        self.result(reward)
+    }
+
+    fn result(&mut self, reward: f32) -> StepResult {
+        let level = self.app.world().get_resource::<State<LevelState>>().map_or(0, |l| l.get().number());
+        StepResult {
+            observation: self.observe(),
+            reward,
+            done: self.is_done(),
+            truncated: !self.is_done() && self.steps >= self.config.max_steps,
+            info: StepInfo {
+                score: self.score,
+                lives: self.lives,
+                level,
+                steps: self.steps,
+                won: self.state() == GameState::Win,
+            },
+        }
+    }
+
+    fn positions<F: bevy::ecs::query::QueryFilter>(&mut self) -> Vec<[f32; 2]> {
+        let world = self.app.world_mut();
+        world.query_filtered::<&Transform, F>()
+            .iter(world)
+            .map(|t| [t.translation.x, t.translation.y])
+            .collect()
+    }
+
+    pub fn observe(&mut self) -> Observation {
+        let player = self.positions::<With<PlayerStruct>>().first().copied();
+        let mut enemies = self.positions::<With<EnemiesStructInLevel1>>();
+        enemies.extend(self.positions::<With<EnemiesStructInLevel2>>());
+        enemies.extend(self.positions::<With<EnemiesStructInLevel3>>());
+        let player_lasers = self.positions::<With<LasersPlayerStruct>>();
+        let enemy_lasers = self.positions::<With<LasersEnemiesStruct>>();
+
+        match self.config.obs {
+            ObservationKind::Positions => Observation::Positions { player, enemies, player_lasers, enemy_lasers },
+            ObservationKind::Grid => {
+                let mut cells = vec![CELL_EMPTY; GRID_WIDTH * GRID_HEIGHT];
+                let layers = [
+                    (enemies, CELL_ENEMY),
+                    (player_lasers, CELL_PLAYER_LASER),
+                    (enemy_lasers, CELL_ENEMY_LASER),
+                    (player.into_iter().collect(), CELL_PLAYER),
+                ];
+                for (points, value) in layers {
+                    for [x, y] in points {
+                        if let Some(index) = grid_index(x, y) {
+                            cells[index] = value;
+                        }
+                    }
+                }
+                Observation::Grid { width: GRID_WIDTH, height: GRID_HEIGHT, cells }
+            }
+        }
+    }
+}
+
+pub fn grid_index(x: f32, y: f32) -> Option<usize> {
+    let column = ((x + ARENA_WIDTH / 2.0) / ARENA_WIDTH * GRID_WIDTH as f32).floor();
+    let row = ((ARENA_HEIGHT / 2.0 - y) / ARENA_HEIGHT * GRID_HEIGHT as f32).floor();
+    if column < 0.0 || row < 0.0 || column >= GRID_WIDTH as f32 || row >= GRID_HEIGHT as f32 {
+        return None;
+    }
+    Some(row as usize * GRID_WIDTH + column as usize)
+}
+
+pub fn spec() -> serde_json::Value {
+    serde_json::json!({
+        "action": { "left": "bool", "right": "bool", "fire": "bool" },
+        "observations": ["positions", "grid"],
+        "grid": {
+            "width": GRID_WIDTH,
+            "height": GRID_HEIGHT,
+            "cells": {
+                "empty": CELL_EMPTY,
+                "player": CELL_PLAYER,
+                "enemy": CELL_ENEMY,
+                "player_laser": CELL_PLAYER_LASER,
+                "enemy_laser": CELL_ENEMY_LASER,
+            },
+        },
+        "defaults": {
+            "frame_skip": RlConfig::default().frame_skip,
+            "max_steps": RlConfig::default().max_steps,
+        },
+    })
+}
+
+pub fn handle_request(env: &mut Option<RlEnv>, line: &str) -> (String, bool) {
+    let request: RlRequest = match serde_json::from_str(line) {
+        Ok(request) => request,
+        Err(error) => return (serde_json::json!({ "error": error.to_string() }).to_string(), false),
+    };
+    match request {
+        RlRequest::Reset { config } => {
+            let (new_env, result) = RlEnv::reset(config);
+            *env = Some(new_env);
+            (serde_json::to_string(&result).unwrap_or_default(), false)
+        }
+        RlRequest::Step { action } => match env.as_mut() {
+            Some(env) => (serde_json::to_string(&env.step(action)).unwrap_or_default(), false),
+            None => (serde_json::json!({ "error": "send reset first" }).to_string(), false),
+        },
+        RlRequest::Spec => (spec().to_string(), false),
+        RlRequest::Close => (serde_json::json!({ "closed": true }).to_string(), true),
+    }
+}
+
+pub fn serve_connection(stream: TcpStream) -> io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let reader = BufReader::new(stream);
+    let mut env = None;
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (response, close) = handle_request(&mut env, &line);
+        writer.write_all(response.as_bytes())?;
+        writer.write_all(b"\n")?;
+        if close {
+            break;
+        }
+    }
+    Ok(())
+}
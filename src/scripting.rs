@@ -0,0 +1,413 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use rhai::{CallFnOptions, Dynamic, Engine, EvalAltResult, Map, Scope, AST, FLOAT};
+use crate::animation::AnimationAtlases;
+use crate::game::{GameMode, GameSet};
+use crate::lasers_enemies::{LaserVelocity, spawn_enemy_laser};
+use crate::level_file::EnemySpawn;
+use crate::levels::*;
+use crate::player::*;
+use crate::waves::{EnemyEntry, WaveDirector, advance_waves};
+
+pub const SCRIPT_EXTENSION: &str = "rhai";
+pub const SCRIPT_DIRECTORY: &str = "scripts";
+pub const SCRIPT_MAX_OPERATIONS: u64 = 100_000;
+
+pub type SettledEnemy = (AnyEnemy, Without<EnemyEntry>);
+
+#[derive(Asset, TypePath, Clone, Debug)]
+pub struct Script {
+    pub source: String,
+}
+
+#[derive(Default, TypePath)]
+pub struct ScriptLoader;
+
+impl AssetLoader for ScriptLoader {
+    type Asset = Script;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Script, std::io::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let source = String::from_utf8(bytes)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
+        Ok(Script { source })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[SCRIPT_EXTENSION]
+    }
+}
+
+#[derive(Default, Clone, Copy, Debug)]
+pub struct ScriptWorld {
+    pub player: Option<Vec2>,
+    pub level_time: f32,
+    pub delta: f32,
+    pub volley_in: f32,
+}
+
+#[derive(Default)]
+pub struct ScriptFrame {
+    pub world: ScriptWorld,
+    pub spawns: Vec<EnemySpawn>,
+    pub shots: Vec<(Vec2, Vec2)>,
+}
+
+#[derive(Resource)]
+pub struct ScriptHost {
+    engine: Engine,
+    frame: Arc<Mutex<ScriptFrame>>,
+    asts: HashMap<String, AST>,
+    pub handles: HashMap<String, Handle<Script>>,
+    pub errors: BTreeMap<String, String>,
+}
+
+#[derive(Resource)]
+pub struct LevelScript {
+    pub path: Option<String>,
+    pub started: bool,
+    pub memory: Dynamic,
+}
+
+#[derive(Component)]
+pub struct ScriptedEnemy {
+    pub path: String,
+    pub started: bool,
+    pub memory: Dynamic,
+}
+
+#[derive(Component)]
+pub struct ScriptErrorText;
+
+impl Default for LevelScript {
+    fn default() -> Self {
+        Self {
+            path: None,
+            started: false,
+            memory: Dynamic::from_map(Map::new()),
+        }
+    }
+}
+
+impl ScriptedEnemy {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+            started: false,
+            memory: Dynamic::from_map(Map::new()),
+        }
+    }
+}
+
+fn world_reader(
+    frame: &Arc<Mutex<ScriptFrame>>,
+    read: fn(&ScriptWorld) -> FLOAT,
+) -> impl Fn() -> FLOAT + Send + Sync + 'static {
+    let frame = frame.clone();
+    move || read(&frame.lock().unwrap().world)
+}
+
+fn enemy_kind(name: &str) -> Result<EnemyKind, Box<EvalAltResult>> {
+    match name {
+        "fighter" => Ok(EnemyKind::Fighter),
+        "armored" => Ok(EnemyKind::Armored),
+        _ => Err(format!("невідомий тип ворога «{name}»").into()),
+    }
+}
+
+fn script_engine(frame: &Arc<Mutex<ScriptFrame>>) -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(SCRIPT_MAX_OPERATIONS);
+    engine.on_print(|text| info!("script: {text}"));
+
+    engine.register_fn("player_x", world_reader(frame, |w| w.player.map_or(0.0, |p| p.x) as FLOAT));
+    engine.register_fn("player_y", world_reader(frame, |w| w.player.map_or(0.0, |p| p.y) as FLOAT));
+    engine.register_fn("level_time", world_reader(frame, |w| w.level_time as FLOAT));
+    engine.register_fn("delta", world_reader(frame, |w| w.delta as FLOAT));
+    engine.register_fn("volley_in", world_reader(frame, |w| w.volley_in as FLOAT));
+
+    let f = frame.clone();
+    engine.register_fn("has_player", move || f.lock().unwrap().world.player.is_some());
+
+    let f = frame.clone();
+    engine.register_fn("spawn_enemy", move |x: FLOAT, y: FLOAT| {
+        f.lock().unwrap().spawns.push(EnemySpawn::at(x as f32, y as f32));
+    });
+    let f = frame.clone();
+    engine.register_fn("spawn_enemy", move |x: FLOAT, y: FLOAT, kind: &str| -> Result<(), Box<EvalAltResult>> {
+        let kind = enemy_kind(kind)?;
+        f.lock().unwrap().spawns.push(EnemySpawn { kind, ..EnemySpawn::at(x as f32, y as f32) });
+        Ok(())
+    });
+    let f = frame.clone();
+    engine.register_fn("spawn_enemy", move |x: FLOAT, y: FLOAT, kind: &str, script: &str| -> Result<(), Box<EvalAltResult>> {
+        let kind = enemy_kind(kind)?;
+        f.lock().unwrap().spawns.push(EnemySpawn {
+            kind,
+            script: Some(script.to_string()),
+            ..EnemySpawn::at(x as f32, y as f32)
+        });
+        Ok(())
+    });
+
+    let f = frame.clone();
+    engine.register_fn("fire", move |x: FLOAT, y: FLOAT, vx: FLOAT, vy: FLOAT| {
+        f.lock().unwrap().shots.push((Vec2::new(x as f32, y as f32), Vec2::new(vx as f32, vy as f32)));
+    });
+    engine
+}
+
+impl Default for ScriptHost {
+    fn default() -> Self {
+        let frame = Arc::new(Mutex::new(ScriptFrame::default()));
+        Self {
+            engine: script_engine(&frame),
+            frame,
+            asts: HashMap::new(),
+            handles: HashMap::new(),
+            errors: BTreeMap::new(),
+        }
+    }
+}
+
+impl ScriptHost {
+    pub fn load(&mut self, path: &str, asset_server: &AssetServer) {
+        if !self.handles.contains_key(path) {
+            self.handles.insert(path.to_string(), asset_server.load(format!("{SCRIPT_DIRECTORY}/{path}")));
+        }
+    }
+
+    pub fn compile(&mut self, path: &str, source: &str) {
+        match self.engine.compile(source) {
+            Ok(ast) => {
+                self.asts.insert(path.to_string(), ast);
+                self.errors.remove(path);
+            }
+            Err(error) => {
+                self.asts.remove(path);
+                self.errors.insert(path.to_string(), error.to_string());
+            }
+        }
+    }
+
+    pub fn is_ready(&self, path: &str) -> bool {
+        self.asts.contains_key(path) && !self.errors.contains_key(path)
+    }
+
+    pub fn call(&mut self, path: &str, function: &str, this: &mut Dynamic) {
+        if !self.is_ready(path) {
+            return;
+        }
+        let ast = &self.asts[path];
+        if !ast.iter_functions().any(|f| f.name == function) {
+            return;
+        }
+        let options = CallFnOptions::new().eval_ast(false).bind_this_ptr(this);
+        if let Err(error) = self.engine.call_fn_with_options::<Dynamic>(options, &mut Scope::new(), ast, function, ()) {
+            self.errors.insert(path.to_string(), error.to_string());
+        }
+    }
+
+    pub fn set_world(&self, world: ScriptWorld) {
+        self.frame.lock().unwrap().world = world;
+    }
+
+    pub fn take_requests(&self) -> (Vec<EnemySpawn>, Vec<(Vec2, Vec2)>) {
+        let mut frame = self.frame.lock().unwrap();
+        (std::mem::take(&mut frame.spawns), std::mem::take(&mut frame.shots))
+    }
+}
+
+pub struct ScriptingPlugin;
+
+impl Plugin for ScriptingPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_asset::<Script>()
+            .init_asset_loader::<ScriptLoader>()
+            .init_resource::<ScriptHost>()
+            .init_resource::<LevelScript>()
+            .add_systems(Startup, show_script_errors)
+            .add_systems(Update, (compile_scripts, update_script_errors).chain())
+            .add_systems(FixedUpdate, (run_level_script, run_enemy_scripts, apply_script_requests)
+                .chain()
+                .in_set(GameSet::Movement)
+                .after(advance_waves));
+        for level in [LevelState::Level1, LevelState::Level2, LevelState::Level3] {
+            app.add_systems(OnEnter(level), begin_level_script.run_if(not(resource_equals(GameMode::Versus))));
+        }
+    }
+}
+
+pub fn compile_scripts(
+    mut events: MessageReader<AssetEvent<Script>>,
+    scripts: Res<Assets<Script>>,
+    mut host: ResMut<ScriptHost>,
+) {
+    for event in events.read() {
+        let (AssetEvent::Added { id } | AssetEvent::Modified { id }) = event else {
+            continue;
+        };
+        let Some(script) = scripts.get(*id) else {
+            continue;
+        };
+        let paths: Vec<String> = host.handles.iter()
+            .filter(|(_, handle)| handle.id() == *id)
+            .map(|(path, _)| path.clone())
+            .collect();
+        for path in paths {
+            host.compile(&path, &script.source);
+        }
+    }
+}
+
+pub fn begin_level_script(
+    level: Res<State<LevelState>>,
+    layout: LevelLayout,
+    asset_server: Res<AssetServer>,
+    mut host: ResMut<ScriptHost>,
+    mut script: ResMut<LevelScript>,
+) {
+    *script = LevelScript {
+        path: layout.script(*level.get()),
+        ..default()
+    };
+    if let Some(path) = &script.path {
+        host.load(path, &asset_server);
+    }
+}
+
+fn script_world(time: &Time, timer: &EnemyShootTimer, director: &WaveDirector, player: Option<&Transform>) -> ScriptWorld {
+    ScriptWorld {
+        player: player.map(|t| t.translation.truncate()),
+        level_time: director.elapsed,
+        delta: time.delta_secs(),
+        volley_in: timer.0.remaining_secs(),
+    }
+}
+
+pub fn run_level_script(
+    time: Res<Time>,
+    timer: Res<EnemyShootTimer>,
+    director: Res<WaveDirector>,
+    players: Query<&Transform, ActivePlayer>,
+    mut host: ResMut<ScriptHost>,
+    mut script: ResMut<LevelScript>,
+) {
+    let LevelScript { path, started, memory } = &mut *script;
+    let Some(path) = path.as_deref() else {
+        return;
+    };
+    if !host.is_ready(path) {
+        return;
+    }
+    host.set_world(script_world(&time, &timer, &director, players.iter().next()));
+    if !*started {
+        *started = true;
+        host.call(path, "start", memory);
+    }
+    host.call(path, "update", memory);
+}
+
+pub fn run_enemy_scripts(
+    time: Res<Time>,
+    timer: Res<EnemyShootTimer>,
+    director: Res<WaveDirector>,
+    asset_server: Res<AssetServer>,
+    players: Query<&Transform, (ActivePlayer, Without<ScriptedEnemy>)>,
+    mut host: ResMut<ScriptHost>,
+    mut enemies: Query<(&mut ScriptedEnemy, &mut Transform), SettledEnemy>,
+) {
+    host.set_world(script_world(&time, &timer, &director, players.iter().next()));
+    for (mut enemy, mut transform) in enemies.iter_mut() {
+        let ScriptedEnemy { path, started, memory } = &mut *enemy;
+        host.load(path, &asset_server);
+        if !host.is_ready(path) {
+            continue;
+        }
+        if let Some(mut map) = memory.write_lock::<Map>() {
+            let age = map.get("age").and_then(|v| v.as_float().ok()).unwrap_or(0.0);
+            map.insert("x".into(), (transform.translation.x as FLOAT).into());
+            map.insert("y".into(), (transform.translation.y as FLOAT).into());
+            map.insert("age".into(), (age + time.delta_secs() as FLOAT).into());
+        }
+        if !*started {
+            *started = true;
+            host.call(path, "start", memory);
+        }
+        host.call(path, "update", memory);
+        if let Some(map) = memory.read_lock::<Map>() {
+            let read = |key: &str| map.get(key).and_then(|v| v.as_float().ok()).map(|v| v as f32);
+            if let (Some(x), Some(y)) = (read("x"), read("y")) {
+                transform.translation.x = x;
+                transform.translation.y = y;
+            }
+        }
+    }
+}
+
+pub fn apply_script_requests(
+    mut commands: Commands,
+    atlases: Res<AnimationAtlases>,
+    asset_server: Res<AssetServer>,
+    director: Res<WaveDirector>,
+    host: Res<ScriptHost>,
+) {
+    let (spawns, shots) = host.take_requests();
+    let Some(level) = director.level else {
+        return;
+    };
+    for spawn in spawns {
+        spawn_enemy(&mut commands, &atlases, level, &spawn, 0);
+    }
+    for (position, velocity) in shots {
+        let laser = spawn_enemy_laser(&mut commands, &asset_server, position);
+        commands.entity(laser).insert(LaserVelocity(velocity));
+    }
+}
+
+pub fn show_script_errors(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font: asset_server.load("fonts/e-UkraineHead-Medium.otf"),
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::srgb(1.0, 0.4, 0.4)),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            right: Val::Px(10.0),
+            max_width: Val::Px(560.0),
+            ..default()
+        },
+        ScriptErrorText,
+    ));
+}
+
+pub fn update_script_errors(host: Res<ScriptHost>, mut text_query: Query<&mut Text, With<ScriptErrorText>>) {
+    if !host.is_changed() {
+        return;
+    }
+    let errors = host.errors.iter()
+        .map(|(path, error)| format!("{SCRIPT_DIRECTORY}/{path}: {error}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    for mut text in text_query.iter_mut() {
+        if text.0 != errors {
+            text.0 = errors.clone();
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use bevy::prelude::*;
+use crate::levels::LevelState;
+
+#[derive(Resource, Default)]
+pub struct Score(pub u32);
+
+#[derive(Component)]
+pub struct Lives(pub u32);
+
+#[derive(Component, Default)]
+pub struct PlayerScore(pub u32);
+
+pub const STARTING_LIVES: u32 = 1;
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Score>()
+            .init_resource::<LevelStats>()
+            .add_systems(OnEnter(LevelState::Level1), reset_level_stats)
+            .add_systems(OnEnter(LevelState::Level2), reset_level_stats)
+            .add_systems(OnEnter(LevelState::Level3), reset_level_stats);
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct LevelStats {
+    pub shots_fired: u32,
+    pub hits: u32,
+    pub enemies_destroyed: u32,
+    pub lives_lost: u32,
+    pub started_at: f32,
+}
+
+impl LevelStats {
+    pub fn accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            0.0
+        } else {
+            self.hits as f32 / self.shots_fired as f32
+        }
+    }
+
+    pub fn bonus(&self, time_taken: f32) -> u32 {
+        let accuracy_bonus = (self.accuracy() * 1000.0).round() as u32;
+        let time_bonus = ((60.0 - time_taken).max(0.0) * 10.0).round() as u32;
+        accuracy_bonus + time_bonus
+    }
+}
+
+pub const ENEMY_POINTS: u32 = 100;
+
+pub fn reset_level_stats(time: Res<Time>, mut stats: ResMut<LevelStats>) {
+    *stats = LevelStats {
+        started_at: time.elapsed_secs(),
+        ..default()
+    };
+}
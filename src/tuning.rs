@@ -0,0 +1,111 @@
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::levels::LevelState;
+
+pub const TUNING_FILE: &str = "game.tuning.ron";
+pub const TUNING_EXTENSION: &str = "tuning.ron";
+
+#[derive(Asset, Resource, TypePath, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct GameTuning {
+    pub player_laser_speed: f32,
+    pub enemy_laser_speed: f32,
+    pub enemy_shoot_interval: f32,
+    pub level_waves: [Vec<usize>; 3],
+    pub enemy_spacing: [f32; 3],
+}
+
+impl Default for GameTuning {
+    fn default() -> Self {
+        Self {
+            player_laser_speed: 450.0,
+            enemy_laser_speed: 365.0,
+            enemy_shoot_interval: 3.5,
+            level_waves: [vec![4, 3], vec![5, 5, 5], vec![7, 6, 6, 6]],
+            enemy_spacing: [100.0, 80.0, 60.0],
+        }
+    }
+}
+
+impl GameTuning {
+    pub fn from_ron(bytes: &[u8]) -> Result<Self, ron::error::SpannedError> {
+        ron::de::from_bytes(bytes)
+    }
+
+    pub fn waves(&self, level: LevelState) -> &[usize] {
+        &self.level_waves[level.number() as usize - 1]
+    }
+
+    pub fn spacing(&self, level: LevelState) -> f32 {
+        self.enemy_spacing[level.number() as usize - 1]
+    }
+}
+
+#[derive(Resource)]
+pub struct TuningHandle(pub Handle<GameTuning>);
+
+#[derive(Default, TypePath)]
+pub struct TuningLoader;
+
+impl AssetLoader for TuningLoader {
+    type Asset = GameTuning;
+    type Settings = ();
+    type Error = std::io::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<GameTuning, std::io::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        GameTuning::from_ron(&bytes).map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[TUNING_EXTENSION]
+    }
+}
+
+pub struct TuningPlugin;
+
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<GameTuning>()
+            .init_asset::<GameTuning>()
+            .init_asset_loader::<TuningLoader>()
+            .add_systems(Startup, load_tuning)
+            .add_systems(PreUpdate, apply_tuning);
+    }
+}
+
+pub fn load_tuning(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(TuningHandle(asset_server.load(TUNING_FILE)));
+}
+
+pub fn apply_tuning(
+    mut events: MessageReader<AssetEvent<GameTuning>>,
+    assets: Res<Assets<GameTuning>>,
+    handle: Option<Res<TuningHandle>>,
+    mut tuning: ResMut<GameTuning>,
+) {
+    let Some(handle) = handle else {
+        return;
+    };
+    for event in events.read() {
+        if !event.is_loaded_with_dependencies(&handle.0) && !event.is_modified(&handle.0) {
+            continue;
+        }
+        let Some(loaded) = assets.get(&handle.0) else {
+            continue;
+        };
+        if *tuning != *loaded {
+            *tuning = loaded.clone();
+            info!("tuning reloaded from {TUNING_FILE}");
+        }
+    }
+}
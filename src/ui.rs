@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use bevy::prelude::*;
+use crate::game::{GameMode, GameState};
+use crate::versus::VersusMatch;
+
+pub const DEFAULT_STRINGS: &[(&str, &str)] = &[
+    ("start", "Гра не розпочата, натисніть на S для старту гри\nабо на C для гри удвох, V — дуель, N — мережева гра, E — редактор рівнів, M — моди, T — досягнення"),
+    ("pause", "Пауза"),
+    ("game_over", "Гра програна! Натисніть на R для рестарту гри"),
+    ("win", "Ви виграли гру! Натисніть R для рестарту"),
+];
+
+#[derive(Resource, Clone, PartialEq, Debug)]
+pub struct Localization(pub HashMap<String, String>);
+
+impl Default for Localization {
+    fn default() -> Self {
+        Self(DEFAULT_STRINGS.iter().map(|(key, text)| (key.to_string(), text.to_string())).collect())
+    }
+}
+
+impl Localization {
+    pub fn with_overrides(overrides: &HashMap<String, String>) -> Self {
+        let mut localization = Self::default();
+        localization.0.extend(overrides.clone());
+        localization
+    }
+
+    pub fn text(&self, key: &str) -> String {
+        self.0.get(key).cloned().unwrap_or_else(|| key.to_string())
+    }
+}
+
+#[derive(Component)]
+pub struct PauseStruct;
+
+#[derive(Component)]
+pub struct GameOverStruct;
+
+#[derive(Component)]
+pub struct WinStruct;
+
+#[derive(Component)]
+pub struct NotStartedStruct;
+
+pub struct UiPlugin;
+
+impl Plugin for UiPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Localization>()
+            .add_systems(OnEnter(GameState::NotStarted), show_start_text)
+            .add_systems(OnEnter(GameState::Pause), show_pause_text)
+            .add_systems(OnEnter(GameState::GameOver), show_game_over_text)
+            .add_systems(OnEnter(GameState::Win), show_win_text);
+    }
+}
+
+fn full_screen_node() -> Node {
+    Node {
+        position_type: PositionType::Absolute,
+        width: Val::Percent(100.0),
+        height: Val::Percent(100.0),
+        justify_content: JustifyContent::Center,
+        align_items: AlignItems::Center,
+        ..default()
+    }
+}
+
+pub fn show_start_text(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    localization: Res<Localization>,
+) {
+    commands.spawn((
+        full_screen_node(),
+        NotStartedStruct,
+        DespawnOnExit(GameState::NotStarted),
+    )).with_children(|p| {
+        p.spawn((
+            Text::new(localization.text("start")),
+            TextFont {
+                font: asset_server.load("fonts/e-ukrainehead-bold_w.ttf"),
+                font_size: 25.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+        ));
+    });
+}
+
+pub fn show_pause_text(mut commands: Commands, asset_server: Res<AssetServer>, localization: Res<Localization>) {
+    commands.spawn((
+        full_screen_node(),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.55)),
+        PauseStruct,
+        DespawnOnExit(GameState::Pause),
+    )).with_children(|parent| {
+        parent.spawn((
+            Text::new(localization.text("pause")),
+            TextFont {
+                font: asset_server.load("fonts/e-UkraineHead-Medium.otf"),
+                font_size: 60.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        ));
+    });
+
+    commands.spawn((
+        AudioPlayer::new(asset_server.load("sounds/pause.ogg")),
+        PlaybackSettings::ONCE,
+    ));
+}
+
+pub fn show_game_over_text(mut commands: Commands, asset_server: Res<AssetServer>, localization: Res<Localization>) {
+    commands.spawn((
+        full_screen_node(),
+        GameOverStruct,
+        DespawnOnExit(GameState::GameOver),
+    )).with_children(|parent| {
+        parent.spawn((
+            Text::new(localization.text("game_over")),
+            TextFont {
+                font: asset_server.load("fonts/e-Ukraine-Bold.otf"),
+                font_size: 25.0,
+                ..default()
+            },
+            TextColor(Color::WHITE)
+        ));
+    });
+    commands.spawn((
+        AudioPlayer::new(asset_server.load("sounds/game_over.ogg")),
+        PlaybackSettings::ONCE,
+    ));
+}
+
+pub fn show_win_text(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mode: Res<GameMode>,
+    versus: Option<Res<VersusMatch>>,
+    localization: Res<Localization>,
+) {
+    let text = match (*mode, versus) {
+        (GameMode::Versus, Some(versus)) => format!(
+            "Гравець {} переміг у дуелі {}:{}! Натисніть R для рестарту",
+            versus.winner().unwrap_or(0) + 1,
+            versus.wins[0],
+            versus.wins[1]
+        ),
+        _ => localization.text("win"),
+    };
+    commands.spawn((
+        full_screen_node(),
+        WinStruct,
+        DespawnOnExit(GameState::Win),
+    )).with_children(|p| {
+        p.spawn((
+            Text::new(text),
+            TextFont {
+                font: asset_server.load("fonts/e-UkraineHead-Bold.otf"),
+                font_size: 25.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+        ));
+    });
+    commands.spawn((
+        AudioPlayer::new(asset_server.load("sounds/win.ogg")),
+        PlaybackSettings::ONCE,
+    ));
+}
@@ -0,0 +1,324 @@
+use bevy::prelude::*;
+use crate::feel::*;
+use crate::game::{GameMode, GameSet, GameState, GameplayObject, InRun};
+use crate::lasers_enemies::LasersEnemiesStruct;
+use crate::lasers_player::LasersPlayerStruct;
+use crate::particles::*;
+use crate::physics::*;
+use crate::player::*;
+use crate::stats::Lives;
+use crate::ui::NotStartedStruct;
+
+pub type AnyLaser = Or<(With<LasersPlayerStruct>, With<LasersEnemiesStruct>)>;
+
+pub const VERSUS_LIVES: u32 = 3;
+pub const BEST_OF_OPTIONS: [u32; 3] = [1, 3, 5];
+pub const COVER_HEALTH: u32 = 3;
+pub const ROUND_OVER_SECONDS: f32 = 2.0;
+
+#[derive(Resource)]
+pub struct VersusSettings {
+    pub best_of: u32,
+}
+
+impl Default for VersusSettings {
+    fn default() -> Self {
+        Self { best_of: 3 }
+    }
+}
+
+#[derive(Resource)]
+pub struct VersusMatch {
+    pub best_of: u32,
+    pub wins: [u32; 2],
+    pub round: u32,
+    pub round_over: Option<Timer>,
+    pub round_winner: Option<usize>,
+}
+
+impl VersusMatch {
+    pub fn new(best_of: u32) -> Self {
+        Self {
+            best_of,
+            wins: [0, 0],
+            round: 1,
+            round_over: None,
+            round_winner: None,
+        }
+    }
+
+    pub fn wins_needed(&self) -> u32 {
+        self.best_of / 2 + 1
+    }
+
+    pub fn winner(&self) -> Option<usize> {
+        self.wins.iter().position(|w| *w >= self.wins_needed())
+    }
+
+    pub fn end_round(&mut self, winner: usize) {
+        self.wins[winner] += 1;
+        self.round_winner = Some(winner);
+        self.round_over = Some(Timer::from_seconds(ROUND_OVER_SECONDS, TimerMode::Once));
+    }
+}
+
+#[derive(Component)]
+pub struct CoverStruct;
+
+#[derive(Component)]
+pub struct CoverHealth(pub u32);
+
+#[derive(Component)]
+pub struct VersusHudText;
+
+#[derive(Component)]
+pub struct VersusSettingsText;
+
+pub struct VersusPlugin;
+
+impl Plugin for VersusPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<VersusSettings>()
+            .add_systems(OnEnter(GameState::NotStarted), show_versus_settings)
+            .add_systems(Update, versus_settings_keys.run_if(in_state(GameState::NotStarted)))
+            .add_systems(OnEnter(InRun), start_versus_match.run_if(resource_equals(GameMode::Versus)))
+            .add_systems(FixedUpdate, (collision_lasers_with_cover, versus_hits)
+                .chain()
+                .in_set(GameSet::Collision)
+                .run_if(resource_equals(GameMode::Versus)))
+            .add_systems(FixedUpdate, update_versus_round
+                .in_set(GameSet::Resolution)
+                .run_if(resource_equals(GameMode::Versus)))
+            .add_systems(Update, update_versus_hud
+                .in_set(GameSet::Presentation)
+                .run_if(resource_exists::<VersusMatch>));
+    }
+}
+
+fn versus_settings_line(settings: &VersusSettings) -> String {
+    format!("B — дуель до {} перемог з {} раундів", settings.best_of / 2 + 1, settings.best_of)
+}
+
+pub fn show_versus_settings(
+    mut commands: Commands,
+    settings: Res<VersusSettings>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            bottom: Val::Px(70.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        NotStartedStruct,
+        DespawnOnExit(GameState::NotStarted),
+    )).with_children(|p| {
+        p.spawn((
+            Text::new(versus_settings_line(&settings)),
+            TextFont {
+                font: asset_server.load("fonts/e-ukrainehead-bold_w.ttf"),
+                font_size: 16.0,
+                ..default()
+            },
+            TextColor(Color::srgb(0.8, 0.8, 0.8)),
+            VersusSettingsText,
+        ));
+    });
+}
+
+pub fn versus_settings_keys(
+    key_code: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<VersusSettings>,
+    mut text_query: Query<&mut Text, With<VersusSettingsText>>,
+) {
+    if key_code.just_pressed(KeyCode::KeyB) {
+        let next = BEST_OF_OPTIONS.iter()
+            .position(|n| *n == settings.best_of)
+            .map_or(0, |i| (i + 1) % BEST_OF_OPTIONS.len());
+        settings.best_of = BEST_OF_OPTIONS[next];
+        for mut text in text_query.iter_mut() {
+            text.0 = versus_settings_line(&settings);
+        }
+    }
+}
+
+pub fn spawn_cover(commands: &mut Commands) {
+    for bunker_x in [-330.0, -110.0, 110.0, 330.0] {
+        for column in 0..3 {
+            for row in 0..2 {
+                let x = bunker_x + (column as f32 - 1.0) * 26.0;
+                let y = (row as f32 - 0.5) * 18.0;
+                commands.spawn((
+                    Sprite::from_color(Color::srgb(0.4, 0.8, 0.5), Vec2::new(24.0, 16.0)),
+                    Transform::from_xyz(x, y, 0.4),
+                    Collider::new(Vec2::new(12.0, 8.0)),
+                    CoverStruct,
+                    CoverHealth(COVER_HEALTH),
+                    GameplayObject,
+                    DespawnOnExit(InRun)
+                ));
+            }
+        }
+    }
+}
+
+pub fn start_versus_match(
+    mut commands: Commands,
+    settings: Res<VersusSettings>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.insert_resource(VersusMatch::new(settings.best_of));
+    spawn_cover(&mut commands);
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            top: Val::Px(10.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        DespawnOnExit(InRun),
+    )).with_children(|p| {
+        p.spawn((
+            Text::new(""),
+            TextFont {
+                font: asset_server.load("fonts/e-UkraineHead-Medium.otf"),
+                font_size: 20.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            TextLayout::new_with_justify(Justify::Center),
+            VersusHudText,
+        ));
+    });
+}
+
+pub fn collision_lasers_with_cover(
+    mut commands: Commands,
+    lasers: Query<(&PhysicalTranslation, &PreviousPhysicalTranslation, Entity), AnyLaser>,
+    mut cover: Query<(&Transform, &Collider, &mut CoverHealth, &mut Sprite, Entity), With<CoverStruct>>,
+) {
+    for (current, previous, laser) in lasers.iter() {
+        for (transform, collider, mut health, mut sprite, block) in cover.iter_mut() {
+            if health.0 == 0 || !collider.swept(transform.translation.truncate(), previous.0, current.0) {
+                continue;
+            }
+            commands.entity(laser).despawn();
+            health.0 -= 1;
+            if health.0 == 0 {
+                commands.entity(block).despawn();
+                spawn_particle_burst(&mut commands, transform.translation, ParticleEmitter::sparks());
+            } else {
+                sprite.color = sprite.color.with_alpha(health.0 as f32 / COVER_HEALTH as f32);
+            }
+            break;
+        }
+    }
+}
+
+pub fn versus_hits(
+    mut commands: Commands,
+    mut versus: ResMut<VersusMatch>,
+    mut players: Query<(&Transform, &Collider, &PlayerSide, &PlayerId, &mut Lives, Entity), ActivePlayer>,
+    upward: Query<(&PhysicalTranslation, &PreviousPhysicalTranslation, Entity), With<LasersPlayerStruct>>,
+    downward: Query<(&PhysicalTranslation, &PreviousPhysicalTranslation, Entity), With<LasersEnemiesStruct>>,
+    mut shake: MessageWriter<ScreenShake>,
+    mut hit_stop: MessageWriter<HitStop>,
+    mut flash: MessageWriter<ScreenFlash>,
+) {
+    if versus.round_over.is_some() {
+        return;
+    }
+    for (transform, collider, side, id, mut lives, entity) in players.iter_mut() {
+        let incoming: Vec<_> = match side {
+            PlayerSide::Bottom => downward.iter().collect(),
+            PlayerSide::Top => upward.iter().collect(),
+        };
+        for (current, previous, laser) in incoming {
+            if !collider.swept(transform.translation.truncate(), previous.0, current.0) {
+                continue;
+            }
+            commands.entity(laser).despawn();
+            lives.0 = lives.0.saturating_sub(1);
+            shake.write(ScreenShake(0.5));
+            flash.write(ScreenFlash(player_color(*id).with_alpha(0.4)));
+            if lives.0 == 0 {
+                spawn_particle_burst(&mut commands, transform.translation, ParticleEmitter::explosion());
+                hit_stop.write(HitStop(0.25));
+                commands.entity(entity).insert(Downed::default());
+                versus.end_round(1 - id.0);
+            }
+            break;
+        }
+        if versus.round_over.is_some() {
+            return;
+        }
+    }
+}
+
+pub fn update_versus_round(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut versus: ResMut<VersusMatch>,
+    mut players: Query<(&PlayerSide, &mut Transform, &mut Lives, Entity), With<PlayerStruct>>,
+    lasers: Query<Entity, AnyLaser>,
+    cover: Query<Entity, With<CoverStruct>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    let Some(timer) = versus.round_over.as_mut() else {
+        return;
+    };
+    if !timer.tick(time.delta()).is_finished() {
+        return;
+    }
+
+    if versus.winner().is_some() {
+        next_state.set(GameState::Win);
+        return;
+    }
+
+    versus.round += 1;
+    versus.round_over = None;
+    versus.round_winner = None;
+    for entity in lasers.iter().chain(cover.iter()) {
+        commands.entity(entity).despawn();
+    }
+    spawn_cover(&mut commands);
+    for (side, mut transform, mut lives, entity) in players.iter_mut() {
+        transform.translation.x = 0.0;
+        transform.translation.y = side_y(*side);
+        lives.0 = VERSUS_LIVES;
+        commands.entity(entity).remove::<Downed>();
+    }
+}
+
+pub fn update_versus_hud(
+    versus: Res<VersusMatch>,
+    players: Query<(&PlayerId, &Lives), With<PlayerStruct>>,
+    mut text_query: Query<&mut Text, With<VersusHudText>>,
+) {
+    let mut lives = [0, 0];
+    for (id, player_lives) in players.iter() {
+        if let Some(slot) = lives.get_mut(id.0) {
+            *slot = player_lives.0;
+        }
+    }
+    let mut hud = format!(
+        "Раунд {}   Гравець 1  {} : {}  Гравець 2   (до {} перемог)\nЖиття: {} / {}",
+        versus.round,
+        versus.wins[0],
+        versus.wins[1],
+        versus.wins_needed(),
+        lives[0],
+        lives[1]
+    );
+    if let Some(winner) = versus.round_winner {
+        hud += &format!("\nРаунд виграв Гравець {}!", winner + 1);
+    }
+    for mut text in text_query.iter_mut() {
+        text.0 = hud.clone();
+    }
+}
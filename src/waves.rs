@@ -0,0 +1,244 @@
+use std::ops::Range;
+use bevy::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use crate::animation::AnimationAtlases;
+use crate::game::GameSet;
+use crate::level_file::EnemySpawn;
+use crate::levels::*;
+
+pub const ENTRY_SPEED: f32 = 320.0;
+pub const ENTRY_STAGGER: f32 = 0.15;
+pub const WAVE_INTERVAL: f32 = 12.0;
+pub const WAVE_CLEARED_FRACTION: f32 = 0.5;
+const ENTRY_TOP_Y: f32 = 440.0;
+const ENTRY_SIDE_X: f32 = 540.0;
+
+#[derive(Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EntryPath {
+    #[default]
+    InPlace,
+    Top,
+    Left,
+    Right,
+}
+
+impl EntryPath {
+    pub fn start(&self, target: Vec2) -> Vec2 {
+        match self {
+            EntryPath::InPlace => target,
+            EntryPath::Top => Vec2::new(target.x, ENTRY_TOP_Y),
+            EntryPath::Left => Vec2::new(-ENTRY_SIDE_X, target.y),
+            EntryPath::Right => Vec2::new(ENTRY_SIDE_X, target.y),
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            EntryPath::InPlace => EntryPath::Top,
+            EntryPath::Top => EntryPath::Left,
+            EntryPath::Left => EntryPath::Right,
+            EntryPath::Right => EntryPath::InPlace,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EntryPath::InPlace => "на місці",
+            EntryPath::Top => "згори",
+            EntryPath::Left => "зліва",
+            EntryPath::Right => "справа",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct Wave {
+    #[serde(default)]
+    pub at: Option<f32>,
+    #[serde(default)]
+    pub cleared: Option<f32>,
+    #[serde(default)]
+    pub entry: EntryPath,
+    pub enemies: Vec<EnemySpawn>,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct PendingSpawn {
+    pub at: f32,
+    pub wave: usize,
+    pub spawn: EnemySpawn,
+}
+
+#[derive(Component)]
+pub struct WaveMember(pub usize);
+
+#[derive(Component)]
+pub struct EnemyEntry {
+    pub target: Vec2,
+}
+
+#[derive(Resource, Default, Clone, PartialEq, Debug)]
+pub struct WaveDirector {
+    pub level: Option<LevelState>,
+    pub waves: Vec<Wave>,
+    pub next_wave: usize,
+    pub elapsed: f32,
+    pub pending: Vec<PendingSpawn>,
+    spawned: usize,
+}
+
+impl WaveDirector {
+    pub fn begin(&mut self, level: LevelState, waves: Vec<Wave>) {
+        *self = Self {
+            level: Some(level),
+            waves,
+            ..default()
+        };
+    }
+
+    pub fn total_enemies(&self) -> usize {
+        self.waves.iter().map(|w| w.enemies.len()).sum()
+    }
+
+    pub fn all_spawned(&self) -> bool {
+        self.level.is_some() && self.next_wave >= self.waves.len() && self.pending.is_empty()
+    }
+
+    pub fn is_cleared(&self, alive: usize) -> bool {
+        self.all_spawned() && alive == 0
+    }
+
+    fn cleared_fraction(&self, wave: usize, alive: &[usize]) -> f32 {
+        let total = self.waves[wave].enemies.len();
+        if total == 0 {
+            return 1.0;
+        }
+        let pending = self.pending.iter().filter(|p| p.wave == wave).count();
+        let remaining = alive.get(wave).copied().unwrap_or(0) + pending;
+        1.0 - remaining as f32 / total as f32
+    }
+
+    fn wave_triggered(&self, alive: &[usize]) -> bool {
+        let wave = &self.waves[self.next_wave];
+        let cleared = match self.next_wave {
+            0 => 1.0,
+            n => self.cleared_fraction(n - 1, alive),
+        };
+        match (wave.at, wave.cleared) {
+            (None, None) => cleared >= 1.0,
+            (at, fraction) => at.is_some_and(|t| self.elapsed >= t) || fraction.is_some_and(|f| cleared >= f),
+        }
+    }
+
+    pub fn tick(&mut self, delta: f32, alive: &[usize]) -> Vec<(usize, EnemySpawn, EntryPath)> {
+        if self.level.is_none() {
+            return Vec::new();
+        }
+        self.elapsed += delta;
+        while self.next_wave < self.waves.len() && self.wave_triggered(alive) {
+            for spawn in &self.waves[self.next_wave].enemies {
+                self.pending.push(PendingSpawn {
+                    at: self.elapsed + spawn.delay,
+                    wave: self.next_wave,
+                    spawn: spawn.clone(),
+                });
+            }
+            self.next_wave += 1;
+        }
+
+        let elapsed = self.elapsed;
+        let (due, waiting): (Vec<_>, Vec<_>) = self.pending.drain(..).partition(|p| p.at <= elapsed);
+        self.pending = waiting;
+        due.into_iter()
+            .map(|p| (p.wave, p.spawn, self.waves[p.wave].entry))
+            .collect()
+    }
+}
+
+pub fn scatter_waves(rng: &mut impl Rng, sizes: &[usize], y: Range<f32>) -> Vec<Wave> {
+    let entries = [EntryPath::Top, EntryPath::Left, EntryPath::Right];
+    sizes.iter().enumerate().map(|(i, size)| Wave {
+        at: (i > 0).then_some(i as f32 * WAVE_INTERVAL),
+        cleared: (i > 0).then_some(WAVE_CLEARED_FRACTION),
+        entry: entries[i % entries.len()],
+        enemies: (0..*size).map(|j| {
+            let x = rng.gen_range(-380.0..380.0_f32);
+            let y = rng.gen_range(y.clone());
+            EnemySpawn { delay: j as f32 * ENTRY_STAGGER, ..EnemySpawn::at(x, y) }
+        }).collect(),
+    }).collect()
+}
+
+pub fn release_enemies(
+    commands: &mut Commands,
+    atlases: &AnimationAtlases,
+    director: &mut WaveDirector,
+    delta: f32,
+    alive: &[usize],
+) {
+    let Some(level) = director.level else {
+        return;
+    };
+    for (wave, spawn, entry) in director.tick(delta, alive) {
+        let target = Vec2::new(spawn.x, spawn.y);
+        let start = entry.start(target);
+        let enemy = spawn_enemy(commands, atlases, level, &spawn, director.spawned % 6);
+        let mut enemy = commands.entity(enemy);
+        enemy.insert(WaveMember(wave));
+        if start != target {
+            enemy.insert((Transform::from_translation(start.extend(0.5)), EnemyEntry { target }));
+        }
+        director.spawned += 1;
+    }
+}
+
+pub struct WavesPlugin;
+
+impl Plugin for WavesPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<WaveDirector>()
+            .add_systems(FixedUpdate, (advance_waves, move_entering_enemies)
+                .chain()
+                .in_set(GameSet::Movement)
+                .before(move_patterned_enemies));
+    }
+}
+
+pub fn advance_waves(
+    mut commands: Commands,
+    time: Res<Time>,
+    atlases: Res<AnimationAtlases>,
+    mut director: ResMut<WaveDirector>,
+    members: Query<&WaveMember>,
+) {
+    if director.level.is_none() {
+        return;
+    }
+    let mut alive = vec![0; director.waves.len()];
+    for member in members.iter() {
+        if let Some(count) = alive.get_mut(member.0) {
+            *count += 1;
+        }
+    }
+    release_enemies(&mut commands, &atlases, &mut director, time.delta_secs(), &alive);
+}
+
+pub fn move_entering_enemies(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut enemies: Query<(&EnemyEntry, &mut Transform, Entity)>,
+) {
+    for (entry, mut transform, entity) in enemies.iter_mut() {
+        let position = transform.translation.truncate();
+        let step = ENTRY_SPEED * time.delta_secs();
+        if position.distance(entry.target) <= step {
+            transform.translation = entry.target.extend(transform.translation.z);
+            commands.entity(entity).remove::<EnemyEntry>();
+        } else {
+            let next = position + (entry.target - position).normalize() * step;
+            transform.translation = next.extend(transform.translation.z);
+        }
+    }
+}
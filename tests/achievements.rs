@@ -0,0 +1,98 @@
+mod common;
+
+use bevy::prelude::*;
+use space_shooter::achievements::*;
+use space_shooter::game::{GameState, GameplayEvent, GodMode};
+use space_shooter::levels::{EnemiesStructInLevel1, LevelState};
+use space_shooter::stats::LevelStats;
+use common::*;
+
+#[test]
+fn counters_unlock_and_progress_is_saved() {
+    let path = std::env::temp_dir().join(format!("space_shooter_achievements_{}.ron", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut achievements = Achievements::load(&path);
+    assert_eq!(achievements.add(LASERS_INTERCEPTED, 99), Vec::<&str>::new());
+    assert_eq!(achievements.add(LASERS_INTERCEPTED, 1), vec!["interceptor"]);
+    assert_eq!(achievements.add(LASERS_INTERCEPTED, 1), Vec::<&str>::new());
+    assert_eq!(achievements.add(ENEMIES_DESTROYED, 1), vec!["first_blood"]);
+    assert!(achievements.unlock("victory"));
+    assert!(!achievements.unlock("victory"));
+    assert!(!achievements.unlock("unknown"));
+    achievements.save().unwrap();
+
+    let loaded = Achievements::load(&path);
+    assert!(loaded.is_unlocked("interceptor") && loaded.is_unlocked("first_blood") && loaded.is_unlocked("victory"));
+    assert_eq!(loaded.counter(LASERS_INTERCEPTED), 101);
+    let text = achievements_menu_text(&loaded);
+    assert!(text.contains("Досягнення (3/7)"), "{text}");
+    assert!(text.contains("[ ] Ветеран — Знищіть 500 ворогів (1/500)"), "{text}");
+    assert!(text.contains("[x] Перехоплювач"), "{text}");
+}
+
+#[test]
+fn gameplay_events_unlock_achievements_with_toasts() {
+    let mut app = app();
+    press(&mut app, KeyCode::KeyS);
+    advance(&mut app, 2.0);
+
+    let enemy = positions::<EnemiesStructInLevel1>(&mut app)[0];
+    spawn_player_laser(&mut app, Vec2::new(enemy.x, enemy.y + 5.0));
+    app.update();
+    app.update();
+    assert!(app.world().resource::<Achievements>().is_unlocked("first_blood"));
+    assert_eq!(count::<AchievementToast>(&mut app), 1);
+
+    spawn_enemy_laser(&mut app, Vec2::new(300.0, 200.0));
+    spawn_player_laser(&mut app, Vec2::new(300.0, 200.0));
+    app.update();
+    app.update();
+    let achievements = app.world().resource::<Achievements>();
+    assert_eq!(achievements.counter(LASERS_INTERCEPTED), 1);
+    assert_eq!(achievements.level_intercepts, 1);
+
+    app.world_mut().resource_mut::<GodMode>().0 = true;
+    spawn_enemy_laser(&mut app, Vec2::new(-300.0, 200.0));
+    spawn_player_laser(&mut app, Vec2::new(-300.0, 200.0));
+    app.update();
+    app.update();
+    assert_eq!(app.world().resource::<Achievements>().counter(LASERS_INTERCEPTED), 1);
+}
+
+#[test]
+fn sharpshooter_ignores_lasers_in_flight() {
+    let mut app = app();
+    press(&mut app, KeyCode::KeyS);
+    let mut stats = app.world_mut().resource_mut::<LevelStats>();
+    stats.shots_fired = 3;
+    stats.hits = 2;
+    spawn_player_laser(&mut app, Vec2::new(-450.0, -200.0));
+    app.world_mut().write_message(GameplayEvent::LevelCleared(LevelState::Level1));
+    app.update();
+    assert!(app.world().resource::<Achievements>().is_unlocked("sharpshooter"));
+}
+
+#[test]
+fn console_cheats_disable_achievements_for_the_run() {
+    let mut app = app();
+    press(&mut app, KeyCode::KeyS);
+    console(&mut app, "spawn enemy 0 200");
+    spawn_player_laser(&mut app, Vec2::new(0.0, 205.0));
+    app.update();
+    app.update();
+    assert!(app.world().resource::<LevelStats>().enemies_destroyed > 0);
+    assert_eq!(app.world().resource::<Achievements>().counter(ENEMIES_DESTROYED), 0);
+}
+
+#[test]
+fn t_opens_achievements_menu() {
+    let mut app = app();
+    press(&mut app, KeyCode::KeyT);
+    assert_eq!(state::<GameState>(&app), GameState::Achievements);
+    assert_eq!(count::<AchievementsMenuText>(&mut app), 1);
+
+    press(&mut app, KeyCode::Escape);
+    assert_eq!(state::<GameState>(&app), GameState::NotStarted);
+    assert_eq!(count::<AchievementsMenuText>(&mut app), 0);
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use bevy::prelude::*;
+use space_shooter::adaptive::*;
+use space_shooter::difficulty::Difficulty;
+use space_shooter::game::GameState;
+use space_shooter::levels::*;
+use space_shooter::stats::LevelStats;
+use space_shooter::waves::WaveDirector;
+use common::*;
+
+fn performance(cleared: bool, lives_lost: u32, accuracy: f32, seconds: f32) -> LevelPerformance {
+    LevelPerformance { level: 1, cleared, lives_lost, accuracy, seconds }
+}
+
+#[test]
+fn clean_clear_raises_pressure_for_next_level() {
+    let mut app = app();
+    press(&mut app, KeyCode::KeyG);
+    assert!(app.world().resource::<AdaptiveDifficulty>().enabled);
+    press(&mut app, KeyCode::KeyS);
+
+    while !app.world().resource::<WaveDirector>().all_spawned() || count::<EnemiesStructInLevel1>(&mut app) > 0 {
+        advance(&mut app, 0.2);
+        for enemy in positions::<EnemiesStructInLevel1>(&mut app) {
+            spawn_player_laser(&mut app, Vec2::new(enemy.x, enemy.y + 5.0));
+        }
+        let mut stats = app.world_mut().resource_mut::<LevelStats>();
+        stats.shots_fired = stats.hits + 1;
+        app.update();
+    }
+    app.update();
+    assert_eq!(state::<GameState>(&app), GameState::Intermission);
+
+    let adaptive = app.world().resource::<AdaptiveDifficulty>();
+    assert_eq!(adaptive.history.len(), 1);
+    let adjustment = adaptive.history[0];
+    assert!(adjustment.performance.cleared);
+    assert_eq!(adjustment.performance.level, 1);
+    assert_eq!(adjustment.performance.lives_lost, 0);
+    assert_eq!((adjustment.from, adjustment.to), (1.0, 1.1));
+
+    advance(&mut app, 7.0);
+    assert_eq!(state::<LevelState>(&app), LevelState::Level2);
+    assert_eq!(app.world().resource::<WaveDirector>().total_enemies(), 18);
+    let interval = app.world().resource::<EnemyShootTimer>().0.duration().as_secs_f32();
+    assert!((interval - 3.5 / 1.1).abs() < 0.001, "{interval}");
+}
+
+#[test]
+fn struggling_lowers_pressure_within_bounds_and_logs() {
+    let path = std::env::temp_dir().join(format!("space_shooter_adaptive_{}.csv", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let mut adaptive = AdaptiveDifficulty { enabled: true, ..AdaptiveDifficulty::with_log(&path) };
+
+    for _ in 0..5 {
+        let adjustment = adaptive.observe(Difficulty::Hard, performance(false, 1, 0.2, 30.0));
+        adaptive.append_log(&adjustment).unwrap();
+    }
+    assert_eq!(adaptive.pressure, MIN_PRESSURE);
+
+    let neutral = adaptive.observe(Difficulty::Hard, performance(true, 0, 0.5, TARGET_CLEAR_SECONDS));
+    assert_eq!((neutral.from, neutral.to), (MIN_PRESSURE, MIN_PRESSURE));
+    assert_eq!(adaptive.observe(Difficulty::Hard, performance(true, 1, 0.9, 20.0)).to, MIN_PRESSURE);
+
+    let log = std::fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(lines.len(), 6);
+    assert_eq!(lines[0], ADAPTIVE_LOG_HEADER);
+    assert_eq!(lines[1], "1,hard,false,1,0.20,30.0,-1.00,1.00,0.90");
+
+    let modifiers = adaptive.adjust(Difficulty::Normal.modifiers());
+    assert_eq!(modifiers.enemy_count, MIN_PRESSURE);
+    assert_eq!(modifiers.lives, 1);
+}
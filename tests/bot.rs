@@ -0,0 +1,74 @@
+mod common;
+
+use bevy::prelude::*;
+use space_shooter::bot::*;
+use space_shooter::game::{GameMode, GameRng, GameState};
+use space_shooter::headless::*;
+use space_shooter::levels::*;
+use space_shooter::player::*;
+use common::*;
+
+#[test]
+fn bot_sidesteps_incoming_laser() {
+    let input = bot_decision(0.0, -370.0, &[Vec2::new(0.0, 200.0)], &[Vec2::new(5.0, -150.0)], false);
+
+    assert!(input.left || input.right);
+    assert!(!input.fire);
+}
+
+#[test]
+fn bot_chases_and_fires_at_nearest_enemy() {
+    let enemies = [Vec2::new(120.0, 200.0), Vec2::new(-300.0, 150.0)];
+
+    let chase = bot_decision(0.0, -370.0, &enemies, &[], true);
+    assert!(chase.right && !chase.left && !chase.fire);
+
+    let aligned = bot_decision(110.0, -370.0, &enemies, &[], true);
+    assert!(aligned.fire);
+    assert!(!bot_decision(110.0, -370.0, &enemies, &[], false).fire);
+}
+
+#[test]
+fn attract_mode_starts_after_idle_and_exits_on_any_key() {
+    let mut app = app();
+    advance(&mut app, ATTRACT_IDLE_SECONDS + 0.5);
+
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert!(app.world().resource::<AttractMode>().active);
+    assert_eq!(*app.world().resource::<GameMode>(), GameMode::Bot);
+    let mut schemes = app.world_mut().query::<&ControlScheme>();
+    assert_eq!(schemes.iter(app.world()).collect::<Vec<_>>(), vec![&ControlScheme::Bot]);
+
+    press(&mut app, KeyCode::KeyK);
+
+    assert_eq!(state::<GameState>(&app), GameState::NotStarted);
+    assert!(!app.world().resource::<AttractMode>().active);
+}
+
+#[test]
+fn seeded_rng_reproduces_level_layout() {
+    let layout = |seed| {
+        let mut app = app();
+        app.insert_resource(GameRng::seeded(seed));
+        press(&mut app, KeyCode::KeyS);
+        let mut positions = positions::<EnemiesStructInLevel1>(&mut app);
+        positions.sort_by(|a, b| a.x.total_cmp(&b.x));
+        positions
+    };
+
+    assert_eq!(layout(9), layout(9));
+    assert_ne!(layout(9), layout(10));
+}
+
+#[test]
+fn balance_report_counts_attempts_and_clears_per_level() {
+    let run = |reached, won| BalanceRun { seed: 0, reached, won, seconds: 0.0 };
+    let report = balance_report(&[run(1, false), run(2, false), run(3, true), run(3, false)]);
+
+    assert_eq!(report[0], LevelBalance { level: 1, attempts: 4, clears: 3 });
+    assert_eq!(report[1], LevelBalance { level: 2, attempts: 3, clears: 2 });
+    assert_eq!(report[2], LevelBalance { level: 3, attempts: 2, clears: 1 });
+
+    let short = balance_run(1, 2.0);
+    assert_eq!(short.reached, 1);
+}
@@ -0,0 +1,85 @@
+#![allow(dead_code)]
+
+use bevy::prelude::*;
+use space_shooter::console::Console;
+use space_shooter::headless::*;
+use space_shooter::lasers_enemies::LasersEnemiesStruct;
+use space_shooter::lasers_player::LasersPlayerStruct;
+use space_shooter::physics::*;
+use space_shooter::player::PlayerId;
+
+pub fn app() -> App {
+    let mut app = headless_app();
+    app.update();
+    app
+}
+
+pub fn press(app: &mut App, key: KeyCode) {
+    app.world_mut().resource_mut::<ButtonInput<KeyCode>>().press(key);
+    app.update();
+    let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    input.release(key);
+    input.clear();
+    app.update();
+}
+
+pub fn console(app: &mut App, line: &str) -> String {
+    app.world_mut().resource_mut::<Console>().submit(line);
+    app.update();
+    app.world().resource::<Console>().log.last().cloned().unwrap_or_default()
+}
+
+pub fn advance(app: &mut App, secs: f32) {
+    let steps = (secs / HEADLESS_STEP).ceil() as u32;
+    for _ in 0..steps {
+        app.update();
+    }
+}
+
+pub fn wait_for_asset(app: &mut App, ready: impl Fn(&World) -> bool) {
+    for _ in 0..200 {
+        app.update();
+        if ready(app.world()) {
+            return;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+}
+
+pub fn spawn_player_laser(app: &mut App, at: Vec2) -> Entity {
+    app.world_mut().spawn((
+        Transform::from_translation(at.extend(0.5)),
+        physical_translation(at),
+        LasersPlayerStruct,
+        PlayerId(0),
+    )).id()
+}
+
+pub fn spawn_enemy_laser(app: &mut App, at: Vec2) -> Entity {
+    app.world_mut().spawn((
+        Transform::from_translation(at.extend(0.5)),
+        physical_translation(at),
+        Collider::new(Vec2::splat(10.0)),
+        LasersEnemiesStruct,
+    )).id()
+}
+
+pub fn teleport(app: &mut App, entity: Entity, to: Vec2) {
+    app.world_mut().entity_mut(entity).insert(physical_translation(to));
+}
+
+pub fn state<S: States + Copy>(app: &App) -> S {
+    *app.world().resource::<State<S>>().get()
+}
+
+pub fn count<C: Component>(app: &mut App) -> usize {
+    app.world_mut().query_filtered::<(), With<C>>().iter(app.world()).count()
+}
+
+pub fn positions<C: Component>(app: &mut App) -> Vec<Vec3> {
+    app.world_mut()
+        .query_filtered::<&Transform, With<C>>()
+        .iter(app.world())
+        .map(|t| t.translation)
+        .collect()
+}
@@ -0,0 +1,138 @@
+mod common;
+
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::*;
+use space_shooter::console::*;
+use space_shooter::feel::HitStop;
+use space_shooter::game::GameState;
+use space_shooter::levels::*;
+use space_shooter::player::PlayerStruct;
+use space_shooter::stats::Lives;
+use common::*;
+
+fn type_text(app: &mut App, text: &str) {
+    for c in text.chars() {
+        app.world_mut().write_message(KeyboardInput {
+            key_code: KeyCode::Unidentified(bevy::input::keyboard::NativeKeyCode::Unidentified),
+            logical_key: if c == ' ' { Key::Space } else { Key::Character(c.to_string().into()) },
+            state: ButtonState::Pressed,
+            text: None,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+    app.update();
+}
+
+#[test]
+fn typed_command_jumps_to_level_three() {
+    let mut app = app();
+    press(&mut app, KeyCode::Backquote);
+    assert!(app.world().resource::<Console>().open);
+
+    press(&mut app, KeyCode::KeyS);
+    assert_eq!(state::<GameState>(&app), GameState::NotStarted);
+
+    type_text(&mut app, "lev");
+    press(&mut app, KeyCode::Tab);
+    assert_eq!(app.world().resource::<Console>().input, "level ");
+    type_text(&mut app, "3");
+    press(&mut app, KeyCode::Enter);
+    app.update();
+
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert_eq!(state::<LevelState>(&app), LevelState::Level3);
+    assert_eq!(app.world().resource::<Console>().history, vec!["level 3".to_string()]);
+
+    press(&mut app, KeyCode::Backquote);
+    assert!(!app.world().resource::<Console>().open);
+}
+
+#[test]
+fn history_and_completion() {
+    let app = app();
+    let commands = app.world().resource::<ConsoleCommands>().clone();
+    let mut console = Console::default();
+
+    console.submit("god");
+    console.submit("seed 5");
+    console.submit("seed 5");
+    console.previous();
+    assert_eq!(console.input, "seed 5");
+    console.previous();
+    console.previous();
+    assert_eq!(console.input, "god");
+    console.next();
+    assert_eq!(console.input, "seed 5");
+    console.next();
+    assert_eq!(console.input, "");
+
+    console.input = "ki".to_string();
+    console.complete(&commands);
+    assert_eq!(console.input, "kill_all ");
+
+    console.input = "state g".to_string();
+    console.complete(&commands);
+    assert_eq!(console.input, "state game");
+    assert_eq!(console.log.last().map(String::as_str), Some("game  gameover"));
+}
+
+#[test]
+fn gameplay_commands() {
+    let mut app = app();
+    press(&mut app, KeyCode::KeyS);
+    app.update();
+
+    let enemies = count::<EnemiesStructInLevel1>(&mut app);
+    assert!(enemies > 0);
+    assert_eq!(console(&mut app, "kill_all"), format!("Знищено ворогів: {enemies}"));
+    assert_eq!(count::<EnemiesStructInLevel1>(&mut app), 0);
+
+    console(&mut app, "spawn armored 10 200");
+    assert_eq!(positions::<EnemiesStructInLevel1>(&mut app), vec![Vec3::new(10.0, 200.0, 0.5)]);
+    assert!(console(&mut app, "spawn boss 0 0").starts_with("Помилка"));
+
+    console(&mut app, "teleport -100 -250");
+    let player = positions::<PlayerStruct>(&mut app)[0];
+    assert_eq!(player.truncate(), Vec2::new(-100.0, -250.0));
+
+    console(&mut app, "god");
+    spawn_enemy_laser(&mut app, player.truncate());
+    app.update();
+    app.update();
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert_eq!(app.world_mut().query::<&Lives>().single(app.world()).unwrap().0, 1);
+
+    console(&mut app, "timescale 0.5");
+    assert_eq!(app.world().resource::<Time<Virtual>>().relative_speed(), 0.5);
+    assert!(console(&mut app, "timescale 0").starts_with("Помилка"));
+
+    console(&mut app, "state win");
+    assert_eq!(state::<GameState>(&app), GameState::Win);
+}
+
+#[test]
+fn timescale_survives_hit_stop() {
+    let mut app = app();
+    console(&mut app, "timescale 0.5");
+    app.world_mut().write_message(HitStop(0.05));
+    app.update();
+    assert_eq!(app.world().resource::<Time<Virtual>>().relative_speed(), 0.0);
+
+    advance(&mut app, 0.2);
+    assert_eq!(app.world().resource::<Time<Virtual>>().relative_speed(), 0.5);
+}
+
+#[test]
+fn subsystems_register_their_own_commands() {
+    let mut app = app();
+    app.add_console_command(ConsoleCommand::new("echo", "echo <текст>", |_, args| Ok(args.join(" "))));
+
+    assert_eq!(console(&mut app, "echo привіт світ"), "привіт світ");
+    console(&mut app, "help");
+    assert!(app.world().resource::<Console>().log.contains(&"echo <текст>".to_string()));
+    assert!(console(&mut app, "nope").starts_with("Помилка"));
+    console(&mut app, "clear");
+    assert!(app.world().resource::<Console>().log.is_empty());
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use bevy::prelude::*;
+use space_shooter::game::{GameMode, GameState};
+use space_shooter::levels::*;
+use space_shooter::player::*;
+use space_shooter::stats::*;
+use common::*;
+
+fn coop() -> App {
+    let mut app = app();
+    press(&mut app, KeyCode::KeyC);
+    app.world_mut().resource_mut::<EnemyShootTimer>().0.pause();
+    app
+}
+
+fn player(app: &mut App, id: usize) -> Entity {
+    let mut players = app.world_mut().query::<(Entity, &PlayerId)>();
+    players.iter(app.world()).find(|(_, p)| p.0 == id).unwrap().0
+}
+
+fn hit(app: &mut App, id: usize) {
+    let entity = player(app, id);
+    let at = app.world().get::<Transform>(entity).unwrap().translation.truncate();
+    spawn_enemy_laser(app, at);
+    app.update();
+    app.update();
+}
+
+#[test]
+fn c_starts_coop_with_two_players() {
+    let mut app = coop();
+
+    assert_eq!(*app.world().resource::<GameMode>(), GameMode::Coop);
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert_eq!(count::<PlayerStruct>(&mut app), 2);
+
+    let mut schemes = app.world_mut().query::<(&PlayerId, &ControlScheme)>();
+    let mut schemes: Vec<_> = schemes.iter(app.world()).map(|(id, s)| (id.0, *s)).collect();
+    schemes.sort_by_key(|(id, _)| *id);
+    assert_eq!(schemes, vec![(0, ControlScheme::KeyboardLeft), (1, ControlScheme::KeyboardRight)]);
+}
+
+#[test]
+fn keyboard_halves_move_their_own_ship() {
+    let mut app = coop();
+    let p1 = player(&mut app, 0);
+    let p2 = player(&mut app, 1);
+    let p1_x = app.world().get::<Transform>(p1).unwrap().translation.x;
+    let p2_x = app.world().get::<Transform>(p2).unwrap().translation.x;
+
+    press(&mut app, KeyCode::KeyD);
+    press(&mut app, KeyCode::ArrowLeft);
+
+    assert_eq!(app.world().get::<Transform>(p1).unwrap().translation.x, p1_x + 25.0);
+    assert_eq!(app.world().get::<Transform>(p2).unwrap().translation.x, p2_x - 25.0);
+}
+
+#[test]
+fn downed_partner_keeps_run_going_and_can_be_revived() {
+    let mut app = coop();
+    hit(&mut app, 0);
+
+    let p1 = player(&mut app, 0);
+    assert!(app.world().get::<Downed>(p1).is_some());
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+
+    let p1_x = app.world().get::<Transform>(p1).unwrap().translation.x;
+    let p2 = player(&mut app, 1);
+    app.world_mut().get_mut::<Transform>(p2).unwrap().translation.x = p1_x + 20.0;
+    advance(&mut app, REVIVE_SECONDS + 0.5);
+
+    assert!(app.world().get::<Downed>(p1).is_none());
+    assert_eq!(app.world().get::<Lives>(p1).unwrap().0, 1);
+}
+
+#[test]
+fn game_over_only_when_both_players_are_down() {
+    let mut app = coop();
+    hit(&mut app, 0);
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+
+    hit(&mut app, 1);
+    assert_eq!(state::<GameState>(&app), GameState::GameOver);
+    assert_eq!(count::<PlayerStruct>(&mut app), 0);
+}
+
+#[test]
+fn kills_are_scored_per_player() {
+    let mut app = coop();
+    advance(&mut app, 0.2);
+    let enemy = positions::<EnemiesStructInLevel1>(&mut app)[0];
+    let laser = spawn_player_laser(&mut app, Vec2::new(enemy.x, enemy.y + 5.0));
+    app.world_mut().entity_mut(laser).insert(PlayerId(1));
+    app.update();
+
+    let p1 = player(&mut app, 0);
+    let p2 = player(&mut app, 1);
+    assert_eq!(app.world().get::<PlayerScore>(p1).unwrap().0, 0);
+    assert_eq!(app.world().get::<PlayerScore>(p2).unwrap().0, ENEMY_POINTS);
+    assert_eq!(app.world().resource::<Score>().0, ENEMY_POINTS);
+}
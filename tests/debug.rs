@@ -0,0 +1,48 @@
+mod common;
+
+use bevy::prelude::*;
+use space_shooter::debug::*;
+use space_shooter::headless::HEADLESS_STEP;
+use space_shooter::levels::EnemiesStructInLevel1;
+use common::*;
+
+fn overlay_text(app: &mut App) -> (String, Visibility) {
+    let (text, visibility) = app.world_mut()
+        .query_filtered::<(&Text, &Visibility), With<DebugText>>()
+        .single(app.world())
+        .unwrap();
+    (text.0.clone(), *visibility)
+}
+
+#[test]
+fn f3_shows_counts_states_and_timers() {
+    let mut app = app();
+    assert_eq!(overlay_text(&mut app).1, Visibility::Hidden);
+
+    press(&mut app, KeyCode::KeyS);
+    app.update();
+    spawn_player_laser(&mut app, Vec2::new(0.0, -200.0));
+    spawn_enemy_laser(&mut app, Vec2::new(300.0, 200.0));
+    press(&mut app, KeyCode::F3);
+
+    let (text, visibility) = overlay_text(&mut app);
+    assert_eq!(visibility, Visibility::Visible);
+    assert!(text.contains("Стан: InGame / Level1"), "{text}");
+    let enemies = count::<EnemiesStructInLevel1>(&mut app);
+    assert!(text.contains(&format!("Гравці: 1  Вороги: {enemies}  Лазери гравців: 1  Лазери ворогів: 1")), "{text}");
+    assert!(text.contains("EnemyShootTimer:"), "{text}");
+    assert!(text.contains("WaveDirector: хвиля 1/2"), "{text}");
+
+    press(&mut app, KeyCode::F3);
+    assert_eq!(overlay_text(&mut app).1, Visibility::Hidden);
+}
+
+#[test]
+fn frame_history_is_bounded() {
+    let mut overlay = DebugOverlay::default();
+    for _ in 0..FRAME_HISTORY + 30 {
+        overlay.record(HEADLESS_STEP);
+    }
+    assert_eq!(overlay.frame_times.len(), FRAME_HISTORY);
+    assert!((overlay.fps() - 60.0).abs() < 0.01);
+}
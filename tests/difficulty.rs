@@ -0,0 +1,145 @@
+mod common;
+
+use bevy::prelude::*;
+use space_shooter::console::ConsoleCheats;
+use space_shooter::difficulty::*;
+use space_shooter::game::GameState;
+use space_shooter::headless::{HEADLESS_STEP, headless_app_with_mods};
+use space_shooter::level_file::{EnemySpawn, LevelFile};
+use space_shooter::levels::EnemyShootTimer;
+use space_shooter::mods::ModSourcePlugin;
+use space_shooter::physics::PhysicalTranslation;
+use space_shooter::player::PlayerStruct;
+use space_shooter::stats::{Lives, Score};
+use space_shooter::waves::{Wave, WaveDirector};
+use common::*;
+
+#[test]
+fn hard_run_scales_enemies_fire_and_records_score() {
+    let mut app = app();
+    press(&mut app, KeyCode::ArrowRight);
+    assert_eq!(*app.world().resource::<Difficulty>(), Difficulty::Hard);
+
+    press(&mut app, KeyCode::KeyS);
+    assert_eq!(app.world().resource::<WaveDirector>().total_enemies(), 9);
+    assert_eq!(app.world().resource::<EnemyShootTimer>().0.duration().as_secs_f32(), 2.625);
+
+    let laser = spawn_enemy_laser(&mut app, Vec2::new(300.0, 200.0));
+    app.update();
+    let y = app.world().get::<PhysicalTranslation>(laser).unwrap().0.y;
+    assert!((y - (200.0 - 365.0 * 1.2 * HEADLESS_STEP)).abs() < 0.01, "{y}");
+
+    app.world_mut().resource_mut::<Score>().0 = 1234;
+    let player = positions::<PlayerStruct>(&mut app)[0];
+    spawn_enemy_laser(&mut app, player.truncate());
+    app.update();
+    app.update();
+    assert_eq!(state::<GameState>(&app), GameState::GameOver);
+
+    let scores = app.world().resource::<HighScores>();
+    assert_eq!(scores.best(Difficulty::Hard), &[1234]);
+    assert!(scores.best(Difficulty::Normal).is_empty());
+}
+
+#[test]
+fn console_difficulty_applies_to_the_next_run_and_skips_its_score() {
+    let mut app = app();
+    press(&mut app, KeyCode::KeyS);
+    assert!(console(&mut app, "difficulty hard").starts_with("Помилка"));
+    assert_eq!(*app.world().resource::<Difficulty>(), Difficulty::Normal);
+    assert!(!app.world().resource::<ConsoleCheats>().run);
+
+    console(&mut app, "kill_all");
+    assert!(app.world().resource::<ConsoleCheats>().run);
+    app.world_mut().resource_mut::<Score>().0 = 1234;
+    console(&mut app, "state gameover");
+    assert_eq!(state::<GameState>(&app), GameState::GameOver);
+    assert!(app.world().resource::<HighScores>().best(Difficulty::Normal).is_empty());
+
+    press(&mut app, KeyCode::KeyR);
+    console(&mut app, "difficulty hard");
+    assert_eq!(*app.world().resource::<Difficulty>(), Difficulty::Hard);
+    press(&mut app, KeyCode::KeyS);
+    assert!(app.world().resource::<ConsoleCheats>().run);
+}
+
+#[test]
+fn easy_run_gives_extra_lives() {
+    let mut app = app();
+    press(&mut app, KeyCode::ArrowLeft);
+    press(&mut app, KeyCode::ArrowLeft);
+    assert_eq!(*app.world().resource::<Difficulty>(), Difficulty::Easy);
+
+    press(&mut app, KeyCode::KeyS);
+    assert_eq!(app.world().resource::<WaveDirector>().total_enemies(), 4);
+    let player = positions::<PlayerStruct>(&mut app)[0];
+    spawn_enemy_laser(&mut app, player.truncate());
+    app.update();
+    app.update();
+
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert_eq!(app.world_mut().query::<&Lives>().single(app.world()).unwrap().0, 2);
+    assert_eq!(Difficulty::Easy.points(100), 50);
+}
+
+#[test]
+fn high_scores_are_ranked_per_difficulty_and_saved() {
+    let path = std::env::temp_dir().join(format!("space_shooter_scores_{}.ron", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let mut scores = HighScores::load(&path);
+    for score in [300, 100, 500, 200, 400] {
+        scores.record(Difficulty::Nightmare, score);
+    }
+    assert_eq!(scores.record(Difficulty::Nightmare, 50), None);
+    assert_eq!(scores.record(Difficulty::Nightmare, 450), Some(1));
+    assert_eq!(scores.record(Difficulty::Easy, 0), None);
+    scores.record(Difficulty::Easy, 70);
+    scores.save().unwrap();
+
+    let loaded = HighScores::load(&path);
+    assert_eq!(loaded.best(Difficulty::Nightmare), &[500, 450, 400, 300, 200]);
+    assert_eq!(loaded.best(Difficulty::Easy), &[70]);
+    assert!(difficulty_line(Difficulty::Easy, &loaded).contains("рекорди: 70"));
+}
+
+#[test]
+fn authored_waves_are_scaled_by_difficulty() {
+    let wave = |xs: &[f32]| Wave {
+        enemies: xs.iter().map(|&x| EnemySpawn::at(x, 100.0)).collect(),
+        ..default()
+    };
+    let waves = vec![wave(&[-100.0, 50.0, 200.0, 300.0, 0.0]), wave(&[120.0])];
+
+    let easy = Difficulty::Easy.modifiers().scale_layout(waves.clone());
+    assert_eq!(easy[0].enemies.iter().map(|e| e.x).collect::<Vec<_>>(), vec![-100.0, 50.0, 200.0]);
+    assert_eq!(easy[1].enemies.len(), 1);
+
+    let nightmare = Difficulty::Nightmare.modifiers().scale_layout(waves.clone());
+    assert_eq!(nightmare[0].enemies.len(), 8);
+    assert_eq!(nightmare[0].enemies[5].x, 100.0);
+    assert!(nightmare[0].enemies[5].delay > 0.0);
+    assert_eq!(nightmare[1].enemies.iter().map(|e| e.x).collect::<Vec<_>>(), vec![120.0, -120.0]);
+
+    assert_eq!(Difficulty::Normal.modifiers().scale_layout(waves.clone()), waves);
+}
+
+#[test]
+fn file_levels_follow_difficulty() {
+    let root = std::env::temp_dir().join(format!("space_shooter_difficulty_mods_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("front/levels")).unwrap();
+    std::fs::write(root.join("front/mod.ron"), r#"(name: "Front", version: "1.0.0", levels: ["levels/five.level.ron"])"#).unwrap();
+    std::fs::write(
+        root.join("front/levels/five.level.ron"),
+        "(waves: [(enemies: [(x: -200.0, y: 100.0), (x: -100.0, y: 100.0), (x: 0.0, y: 100.0), (x: 100.0, y: 100.0), (x: 200.0, y: 100.0)])])",
+    ).unwrap();
+    std::fs::write(root.join("enabled.ron"), r#"["front"]"#).unwrap();
+
+    let mut app = headless_app_with_mods(ModSourcePlugin { root: root.clone() });
+    app.update();
+    wait_for_asset(&mut app, |world| !world.resource::<Assets<LevelFile>>().is_empty());
+    app.insert_resource(Difficulty::Nightmare);
+    press(&mut app, KeyCode::KeyS);
+    assert_eq!(app.world().resource::<WaveDirector>().total_enemies(), 8);
+}
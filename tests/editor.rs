@@ -0,0 +1,163 @@
+mod common;
+
+use bevy::prelude::*;
+use space_shooter::editor::*;
+use space_shooter::game::GameState;
+use space_shooter::level_file::*;
+use space_shooter::levels::*;
+use space_shooter::waves::*;
+use common::*;
+
+fn with_level_file(app: &mut App, level: LevelFile) {
+    let handle = app.world_mut().resource_mut::<Assets<LevelFile>>().add(level);
+    app.world_mut().resource_mut::<LevelFiles>().levels = vec![handle];
+}
+
+#[test]
+fn editor_places_snapped_enemies_and_removes_nearest() {
+    let mut editor = LevelEditor::default();
+    editor.brush.kind = EnemyKind::Armored;
+    editor.brush.delay = 1.5;
+
+    editor.place(Vec2::new(103.0, 148.0));
+    editor.place(Vec2::new(0.0, -390.0));
+    editor.place(Vec2::new(-200.0, 200.0));
+
+    assert_eq!(editor.level.enemy_count(), 2);
+    let first = &editor.level.waves[0].enemies[0];
+    assert_eq!((first.x, first.y, first.kind, first.delay), (100.0, 150.0, EnemyKind::Armored, 1.5));
+
+    assert!(!editor.remove_near(Vec2::new(0.0, 0.0)));
+    assert!(editor.remove_near(Vec2::new(-190.0, 195.0)));
+    assert_eq!(editor.level.enemy_count(), 1);
+}
+
+#[test]
+fn editor_adds_waves_that_follow_the_previous_one() {
+    let mut editor = LevelEditor::default();
+    editor.place(Vec2::new(0.0, 200.0));
+    editor.next_wave();
+    editor.place(Vec2::new(100.0, 200.0));
+    editor.next_wave();
+    editor.next_wave();
+
+    assert_eq!(editor.wave, 0);
+    assert_eq!(editor.level.waves.len(), 2);
+    assert_eq!(editor.level.waves[0].cleared, None);
+    assert_eq!(editor.level.waves[1].cleared, Some(WAVE_CLEARED_FRACTION));
+    assert!(!editor.remove_near(Vec2::new(100.0, 200.0)));
+}
+
+#[test]
+fn level_file_round_trips_through_ron_and_fills_defaults() {
+    let level = LevelFile {
+        script: Some("boss.rhai".to_string()),
+        waves: vec![Wave {
+            at: Some(4.0),
+            entry: EntryPath::Left,
+            enemies: vec![
+                EnemySpawn { movement: EnemyMovement::Sway, fire: EnemyFire::Silent, delay: 2.0, ..EnemySpawn::at(10.0, 20.0) },
+                EnemySpawn { script: Some("zigzag.rhai".to_string()), ..EnemySpawn::at(-30.0, 40.0) },
+            ],
+            ..Wave::default()
+        }],
+    };
+    assert_eq!(LevelFile::from_ron(level.to_ron().unwrap().as_bytes()).unwrap(), level);
+
+    let short = LevelFile::from_ron(b"(waves: [(enemies: [(x: 1.0, y: 2.0)])])").unwrap();
+    assert_eq!(short.waves, vec![Wave { enemies: vec![EnemySpawn::at(1.0, 2.0)], ..Wave::default() }]);
+    assert!(LevelFile::from_ron(b"(waves: [(enemies: [(x: 1.0)])])").is_err());
+
+    let (config, rest) = EditorConfig::from_args(
+        ["--editor", "--host", "7000", "--level", "levels/a.level.ron"].map(String::from)
+    ).unwrap();
+    assert!(config.open);
+    assert_eq!(config.levels, vec!["levels/a.level.ron".to_string()]);
+    assert_eq!(rest, vec!["--host".to_string(), "7000".to_string()]);
+}
+
+#[test]
+fn level_file_layout_spawns_enemies_over_time() {
+    let mut app = app();
+    with_level_file(&mut app, LevelFile {
+        waves: vec![Wave {
+            enemies: vec![
+                EnemySpawn::at(-200.0, 200.0),
+                EnemySpawn { delay: 1.0, ..EnemySpawn::at(200.0, 200.0) },
+            ],
+            ..Wave::default()
+        }],
+        ..LevelFile::default()
+    });
+    press(&mut app, KeyCode::KeyS);
+
+    assert_eq!(positions::<EnemiesStructInLevel1>(&mut app), vec![Vec3::new(-200.0, 200.0, 0.5)]);
+    assert!(!app.world().resource::<WaveDirector>().all_spawned());
+
+    let first = app.world_mut().query_filtered::<Entity, With<EnemiesStructInLevel1>>()
+        .single(app.world())
+        .unwrap();
+    app.world_mut().entity_mut(first).despawn();
+    app.update();
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+
+    advance(&mut app, 1.0);
+    assert_eq!(count::<EnemiesStructInLevel1>(&mut app), 1);
+    assert!(app.world().resource::<WaveDirector>().all_spawned());
+}
+
+#[test]
+fn armored_enemy_takes_two_hits() {
+    let mut app = app();
+    with_level_file(&mut app, LevelFile {
+        waves: vec![Wave {
+            enemies: vec![EnemySpawn { kind: EnemyKind::Armored, ..EnemySpawn::at(200.0, 200.0) }],
+            ..Wave::default()
+        }],
+        ..LevelFile::default()
+    });
+    press(&mut app, KeyCode::KeyS);
+
+    spawn_player_laser(&mut app, Vec2::new(200.0, 200.0));
+    app.update();
+    assert_eq!(count::<EnemiesStructInLevel1>(&mut app), 1);
+
+    spawn_player_laser(&mut app, Vec2::new(200.0, 200.0));
+    app.update();
+    assert_eq!(count::<EnemiesStructInLevel1>(&mut app), 0);
+}
+
+#[test]
+fn preview_plays_edited_level_and_returns_to_editor() {
+    let mut app = app();
+    press(&mut app, KeyCode::KeyE);
+    assert_eq!(state::<GameState>(&app), GameState::Editor);
+
+    app.world_mut().resource_mut::<LevelEditor>().place(Vec2::new(300.0, 250.0));
+    app.update();
+    assert_eq!(count::<EditorMarker>(&mut app), 1);
+
+    press(&mut app, KeyCode::KeyP);
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert_eq!(positions::<EnemiesStructInLevel1>(&mut app), vec![Vec3::new(300.0, 250.0, 0.5)]);
+
+    spawn_player_laser(&mut app, Vec2::new(300.0, 250.0));
+    advance(&mut app, 0.5);
+    assert_eq!(state::<GameState>(&app), GameState::Editor);
+    assert!(app.world().resource::<LevelFiles>().preview.is_none());
+    assert_eq!(count::<EditorMarker>(&mut app), 1);
+}
+
+#[test]
+fn level_files_load_through_asset_server() {
+    let mut app = app();
+    let handle: Handle<LevelFile> = app.world().resource::<AssetServer>().load("levels/example.level.ron");
+    wait_for_asset(&mut app, |world| world.resource::<Assets<LevelFile>>().contains(&handle));
+
+    let level = app.world().resource::<Assets<LevelFile>>().get(&handle).unwrap();
+    assert_eq!(level.enemy_count(), 8);
+    assert_eq!(level.script.as_deref(), Some("reinforcements.rhai"));
+    assert_eq!(level.waves[0].enemies[1].kind, EnemyKind::Armored);
+    assert_eq!(level.waves[1].cleared, Some(0.5));
+    assert_eq!(level.waves[2].entry, EntryPath::Right);
+}
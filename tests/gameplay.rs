@@ -0,0 +1,237 @@
+mod common;
+
+use bevy::prelude::*;
+use bevy::window::WindowFocused;
+use space_shooter::game::GameState;
+use space_shooter::lasers_enemies::LasersEnemiesStruct;
+use space_shooter::lasers_player::LasersPlayerStruct;
+use space_shooter::levels::*;
+use space_shooter::particles::Particle;
+use space_shooter::physics::{PhysicalTranslation, TickRate};
+use space_shooter::player::PlayerStruct;
+use space_shooter::stats::*;
+use space_shooter::waves::WaveDirector;
+use common::*;
+
+fn started() -> App {
+    let mut app = app();
+    press(&mut app, KeyCode::KeyS);
+    app
+}
+
+#[test]
+fn pressing_s_starts_level_1() {
+    let mut app = app();
+    assert_eq!(state::<GameState>(&app), GameState::NotStarted);
+
+    press(&mut app, KeyCode::KeyS);
+
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert_eq!(state::<LevelState>(&app), LevelState::Level1);
+    assert_eq!(app.world().resource::<WaveDirector>().total_enemies(), 7);
+    assert!(count::<EnemiesStructInLevel1>(&mut app) > 0);
+    assert_eq!(count::<PlayerStruct>(&mut app), 1);
+}
+
+#[test]
+fn killing_all_enemies_advances_level() {
+    let mut app = started();
+
+    while !app.world().resource::<WaveDirector>().all_spawned() || count::<EnemiesStructInLevel1>(&mut app) > 0 {
+        advance(&mut app, 0.2);
+        for enemy in positions::<EnemiesStructInLevel1>(&mut app) {
+            spawn_player_laser(&mut app, Vec2::new(enemy.x, enemy.y + 5.0));
+        }
+        app.update();
+    }
+    app.update();
+    assert_eq!(state::<GameState>(&app), GameState::Intermission);
+
+    advance(&mut app, 7.0);
+
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert_eq!(state::<LevelState>(&app), LevelState::Level2);
+    assert_eq!(app.world().resource::<WaveDirector>().total_enemies(), 15);
+}
+
+#[test]
+fn enemy_laser_on_player_is_game_over() {
+    let mut app = started();
+    let player = positions::<PlayerStruct>(&mut app)[0];
+
+    spawn_enemy_laser(&mut app, player.truncate());
+    app.update();
+    app.update();
+
+    assert_eq!(state::<GameState>(&app), GameState::GameOver);
+    assert_eq!(count::<PlayerStruct>(&mut app), 0);
+}
+
+#[test]
+fn r_restarts_after_game_over() {
+    let mut app = started();
+    let player = positions::<PlayerStruct>(&mut app)[0];
+    spawn_enemy_laser(&mut app, player.truncate());
+    app.update();
+    app.update();
+    assert_eq!(state::<GameState>(&app), GameState::GameOver);
+
+    press(&mut app, KeyCode::KeyR);
+
+    assert_eq!(state::<GameState>(&app), GameState::NotStarted);
+    assert!(app.world().get_resource::<State<LevelState>>().is_none());
+    assert_eq!(count::<LasersEnemiesStruct>(&mut app), 0);
+
+    press(&mut app, KeyCode::KeyS);
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert_eq!(state::<LevelState>(&app), LevelState::Level1);
+    assert_eq!(count::<PlayerStruct>(&mut app), 1);
+    assert!(count::<EnemiesStructInLevel1>(&mut app) > 0);
+}
+
+#[test]
+fn p_toggles_pause() {
+    let mut app = started();
+
+    press(&mut app, KeyCode::KeyP);
+    assert_eq!(state::<GameState>(&app), GameState::Pause);
+
+    press(&mut app, KeyCode::KeyP);
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+}
+
+#[test]
+fn game_over_clears_run_entities() {
+    let mut app = started();
+    advance(&mut app, 2.0);
+    press(&mut app, KeyCode::Space);
+    advance(&mut app, 0.1);
+    app.world_mut().resource_mut::<EnemyShootTimer>().0.almost_finish();
+    app.update();
+    assert_eq!(count::<LasersPlayerStruct>(&mut app), 1);
+    assert_eq!(count::<LasersEnemiesStruct>(&mut app), count::<EnemiesStructInLevel1>(&mut app));
+
+    let player = positions::<PlayerStruct>(&mut app)[0];
+    let mut lasers = app.world_mut().query_filtered::<Entity, With<LasersEnemiesStruct>>();
+    let laser = lasers.iter(app.world()).next().unwrap();
+    teleport(&mut app, laser, player.truncate());
+    app.update();
+    app.update();
+
+    assert_eq!(state::<GameState>(&app), GameState::GameOver);
+    assert_eq!(count::<EnemiesStructInLevel1>(&mut app), 0);
+    assert_eq!(count::<LasersPlayerStruct>(&mut app), 0);
+    assert_eq!(count::<LasersEnemiesStruct>(&mut app), 0);
+}
+
+#[test]
+fn new_run_resets_run_resources() {
+    let mut app = started();
+    app.world_mut().resource_mut::<Score>().0 = 500;
+    app.world_mut().resource_mut::<WaveDirector>().elapsed = 30.0;
+    app.world_mut().resource_mut::<EnemyShootTimer>().0.tick(std::time::Duration::from_secs(2));
+
+    app.world_mut().resource_mut::<NextState<GameState>>().set(GameState::Win);
+    app.update();
+    press(&mut app, KeyCode::KeyR);
+    press(&mut app, KeyCode::KeyS);
+
+    assert_eq!(app.world().resource::<Score>().0, 0);
+    let mut lives = app.world_mut().query::<&Lives>();
+    assert!(lives.iter(app.world()).all(|l| l.0 == STARTING_LIVES));
+    assert_eq!(app.world().resource::<WaveDirector>().level, Some(LevelState::Level1));
+    assert!(app.world().resource::<WaveDirector>().elapsed < 1.0);
+    assert!(app.world().resource::<EnemyShootTimer>().0.elapsed_secs() < 1.0);
+    assert_eq!(count::<PlayerStruct>(&mut app), 1);
+}
+
+#[test]
+fn pause_freezes_virtual_clock_and_keeps_scene() {
+    let mut app = started();
+    advance(&mut app, 1.0);
+    let enemies = count::<EnemiesStructInLevel1>(&mut app);
+
+    press(&mut app, KeyCode::KeyP);
+    let shoot_elapsed = app.world().resource::<EnemyShootTimer>().0.elapsed_secs();
+    let virtual_elapsed = app.world().resource::<Time<Virtual>>().elapsed_secs();
+    advance(&mut app, 5.0);
+
+    assert!(app.world().resource::<Time<Virtual>>().is_paused());
+    assert_eq!(app.world().resource::<Time<Virtual>>().elapsed_secs(), virtual_elapsed);
+    assert_eq!(app.world().resource::<EnemyShootTimer>().0.elapsed_secs(), shoot_elapsed);
+    assert_eq!(count::<EnemiesStructInLevel1>(&mut app), enemies);
+    assert_eq!(count::<LasersEnemiesStruct>(&mut app), 0);
+
+    press(&mut app, KeyCode::KeyP);
+    app.update();
+
+    assert!(!app.world().resource::<Time<Virtual>>().is_paused());
+    assert_eq!(count::<LasersEnemiesStruct>(&mut app), 0);
+}
+
+#[test]
+fn losing_window_focus_pauses() {
+    let mut app = started();
+
+    app.world_mut().write_message(WindowFocused { window: Entity::PLACEHOLDER, focused: false });
+    app.update();
+    app.update();
+
+    assert_eq!(state::<GameState>(&app), GameState::Pause);
+}
+
+#[test]
+fn fast_lasers_do_not_tunnel_at_low_tick_rate() {
+    let mut app = started();
+    advance(&mut app, 2.0);
+    app.world_mut().resource_mut::<TickRate>().0 = 5.0;
+    let enemies = count::<EnemiesStructInLevel1>(&mut app);
+
+    let enemy = positions::<EnemiesStructInLevel1>(&mut app)[0];
+    spawn_player_laser(&mut app, Vec2::new(enemy.x, enemy.y - 100.0));
+    advance(&mut app, 1.0);
+
+    assert_eq!(count::<EnemiesStructInLevel1>(&mut app), enemies - 1);
+    assert_eq!(count::<LasersPlayerStruct>(&mut app), 0);
+}
+
+#[test]
+fn laser_speed_is_independent_of_tick_rate() {
+    let mut app = started();
+    let laser = spawn_player_laser(&mut app, Vec2::new(2000.0, -300.0));
+    app.world_mut().resource_mut::<TickRate>().0 = 30.0;
+    app.update();
+    let start = app.world().get::<PhysicalTranslation>(laser).unwrap().0.y;
+
+    advance(&mut app, 1.0);
+
+    let end = app.world().get::<PhysicalTranslation>(laser).unwrap().0.y;
+    assert!((end - start - 450.0).abs() < 20.0);
+}
+
+#[test]
+fn explosion_particles_start_at_the_hit_position() {
+    let mut app = started();
+    advance(&mut app, 2.0);
+    let enemy = positions::<EnemiesStructInLevel1>(&mut app)[0];
+    spawn_player_laser(&mut app, Vec2::new(enemy.x, enemy.y + 5.0));
+    app.update();
+
+    let particles = positions::<Particle>(&mut app);
+    assert!(particles.iter().any(|p| p.truncate().distance(enemy.truncate()) < 30.0));
+}
+
+#[test]
+fn buffered_input_is_applied_once_per_fixed_tick() {
+    let mut app = started();
+    app.world_mut().resource_mut::<TickRate>().0 = 5.0;
+    app.update();
+    let start = positions::<PlayerStruct>(&mut app)[0].x;
+
+    press(&mut app, KeyCode::KeyA);
+    press(&mut app, KeyCode::Space);
+    advance(&mut app, 0.5);
+
+    assert_eq!(positions::<PlayerStruct>(&mut app)[0].x, start - 25.0);
+    assert_eq!(app.world().resource::<LevelStats>().shots_fired, 1);
+}
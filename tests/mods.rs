@@ -0,0 +1,143 @@
+mod common;
+
+use std::path::{Path, PathBuf};
+use bevy::prelude::*;
+use space_shooter::editor::EditorConfig;
+use space_shooter::game::GameState;
+use space_shooter::headless::headless_app_with_mods;
+use space_shooter::level_file::{LevelFile, LevelFiles};
+use space_shooter::levels::*;
+use space_shooter::mods::*;
+use space_shooter::scripting::ScriptHost;
+use space_shooter::ui::Localization;
+use common::*;
+
+fn mods_root(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("space_shooter_mods_{name}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).unwrap();
+    root
+}
+
+fn write(root: &Path, file: &str, contents: &str) {
+    let path = root.join(file);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, contents).unwrap();
+}
+
+fn mod_app(root: &Path) -> App {
+    let mut app = headless_app_with_mods(ModSourcePlugin { root: root.to_path_buf() });
+    app.update();
+    app
+}
+
+const ONE_ENEMY_LEVEL: &str = "(waves: [(enemies: [(x: 120.0, y: 180.0)])])";
+
+#[test]
+fn each_mod_is_validated_on_its_own() {
+    let root = mods_root("validate");
+    write(&root, "good/mod.ron", r#"(name: "Good", version: "1.2.0", levels: ["levels/a.level.ron"])"#);
+    write(&root, "good/levels/a.level.ron", ONE_ENEMY_LEVEL);
+    write(&root, "good/strings.ron", r#"{"pause": "Стоп"}"#);
+    write(&root, "broken/mod.ron", r#"(name: "Broken", version: "one", load_order: 5)"#);
+    write(&root, "broken/levels/b.level.ron", "(waves: [(enemies: [(x: 1.0)])])");
+    write(&root, "broken/strings.ron", r#"{"title": "?"}"#);
+    write(&root, "broken/images/ship.bmp", "");
+    write(&root, "broken/scripts/ai.rhai", "fn update( {");
+    write(&root, "copy/mod.ron", r#"(name: "Good", version: "1.0.0", load_order: 9)"#);
+    write(&root, "empty/readme.txt", "");
+
+    let mut registry = ModRegistry::discover(&root);
+    let folders: Vec<&str> = registry.mods.iter().map(|m| m.folder.as_str()).collect();
+    assert_eq!(folders, vec!["empty", "good", "broken", "copy"]);
+
+    let errors = |folder: &str| registry.mods.iter().find(|m| m.folder == folder).unwrap().errors.clone();
+    assert!(errors("good").is_empty());
+    assert_eq!(errors("empty").len(), 1);
+    assert_eq!(errors("copy").len(), 1);
+    let broken = errors("broken");
+    assert_eq!(broken.len(), 5, "{broken:?}");
+    assert!(broken.iter().any(|e| e.starts_with("levels/b.level.ron")));
+    assert!(broken.iter().any(|e| e.starts_with("images/ship.bmp")));
+
+    registry.toggle(2);
+    assert!(!registry.mods[2].enabled);
+    registry.toggle(1);
+    assert_eq!(registry.content().strings.get("pause").map(String::as_str), Some("Стоп"));
+    assert_eq!(registry.content().levels, vec!["levels/a.level.ron".to_string()]);
+}
+
+#[test]
+fn enabled_mod_overrides_assets_enemies_and_strings() {
+    let root = mods_root("override");
+    write(&root, "front/mod.ron", r#"(name: "Front", version: "1.0.0", levels: ["levels/example.level.ron"])"#);
+    write(&root, "front/levels/example.level.ron", ONE_ENEMY_LEVEL);
+    write(&root, "front/enemies.ron", "{Fighter: (health: Some(3))}");
+    write(&root, "front/strings.ron", r#"{"win": "Перемога"}"#);
+    write(&root, "enabled.ron", r#"["front"]"#);
+
+    let mut app = mod_app(&root);
+    assert_eq!(app.world().resource::<Localization>().text("win"), "Перемога");
+    {
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        app.world_mut().resource_mut::<ScriptHost>().load("zigzag.rhai", &asset_server);
+    }
+    wait_for_asset(&mut app, |world| {
+        world.resource::<ScriptHost>().is_ready("zigzag.rhai") && !world.resource::<Assets<LevelFile>>().is_empty()
+    });
+
+    press(&mut app, KeyCode::KeyS);
+    app.update();
+    assert_eq!(positions::<EnemiesStructInLevel1>(&mut app), vec![Vec3::new(120.0, 180.0, 0.5)]);
+    let health = app.world_mut().query::<&EnemyHealth>().single(app.world()).unwrap().0;
+    assert_eq!(health, 3);
+}
+
+#[test]
+fn mod_levels_are_kept_without_level_arguments() {
+    let root = mods_root("editor_config");
+    write(&root, "front/mod.ron", r#"(name: "Front", version: "1.0.0", levels: ["levels/example.level.ron"])"#);
+    write(&root, "front/levels/example.level.ron", ONE_ENEMY_LEVEL);
+    write(&root, "enabled.ron", r#"["front"]"#);
+
+    let mut app = headless_app_with_mods(ModSourcePlugin { root: root.to_path_buf() });
+    app.insert_resource(EditorConfig::default());
+    app.update();
+    assert_eq!(app.world().resource::<LevelFiles>().levels.len(), 1);
+}
+
+#[test]
+fn headless_app_ignores_local_mods() {
+    let app = app();
+    assert!(app.world().resource::<ModRegistry>().mods.is_empty());
+}
+
+#[test]
+fn mod_menu_toggles_and_remembers_mods() {
+    let root = mods_root("menu");
+    write(&root, "loud/mod.ron", r#"(name: "Loud", version: "0.1.0")"#);
+    write(&root, "loud/strings.ron", r#"{"pause": "Тиша"}"#);
+
+    let mut app = mod_app(&root);
+    press(&mut app, KeyCode::KeyM);
+    assert_eq!(state::<GameState>(&app), GameState::Mods);
+    assert!(mod_menu_text(app.world().resource::<ModRegistry>()).contains("> [ ] Loud 0.1.0"));
+
+    press(&mut app, KeyCode::Enter);
+    press(&mut app, KeyCode::Escape);
+    assert_eq!(state::<GameState>(&app), GameState::NotStarted);
+    assert_eq!(app.world().resource::<Localization>().text("pause"), "Тиша");
+    assert_eq!(*app.world().resource::<ModRegistry>().layers.0.read().unwrap(), vec![root.join("loud")]);
+    assert_eq!(std::fs::read_to_string(root.join(ENABLED_MODS)).unwrap(), r#"["loud"]"#);
+
+    assert!(ModRegistry::discover(&root).mods[0].enabled);
+}
+
+#[test]
+fn shipped_mods_are_valid() {
+    let registry = ModRegistry::discover(Path::new(env!("CARGO_MANIFEST_DIR")).join(MODS_DIRECTORY).as_path());
+    assert!(!registry.mods.is_empty());
+    for game_mod in &registry.mods {
+        assert!(game_mod.is_valid(), "{}: {:?}", game_mod.folder, game_mod.errors);
+    }
+}
@@ -0,0 +1,204 @@
+mod common;
+
+use std::net::SocketAddr;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+use bevy::prelude::*;
+use space_shooter::difficulty::Difficulty;
+use space_shooter::game::GameState;
+use space_shooter::level_file::EnemySpawn;
+use space_shooter::levels::{EnemyDefinition, EnemyDefinitions, EnemyKind};
+use space_shooter::netplay::rollback::Rollback;
+use space_shooter::netplay::*;
+use space_shooter::tuning::GameTuning;
+use space_shooter::waves::Wave;
+use common::*;
+
+fn scripted(player: usize, frame: u32) -> NetInput {
+    let frame = frame + player as u32 * 7;
+    NetInput::new(frame.is_multiple_of(29), frame.is_multiple_of(23), frame.is_multiple_of(11))
+}
+
+#[test]
+fn simulation_is_deterministic() {
+    let mut a = SimState::new(42, SimRules::default());
+    let mut b = SimState::new(42, SimRules::default());
+    for frame in 0..600 {
+        let inputs = [scripted(0, frame), scripted(1, frame)];
+        a.step(inputs);
+        b.step(inputs);
+    }
+
+    assert_eq!(a, b);
+    assert_eq!(a.checksum(), b.checksum());
+    assert_ne!(SimState::new(42, SimRules::default()).checksum(), SimState::new(43, SimRules::default()).checksum());
+}
+
+#[test]
+fn rules_follow_tuning_and_difficulty() {
+    let rules = SimRules::default();
+    assert_eq!(rules.level_waves, [vec![4, 3], vec![5, 5, 5], vec![7, 6, 6, 6]]);
+    assert_eq!(rules.shoot_frames, 210);
+    assert_eq!((rules.player_laser_speed, rules.enemy_laser_speed), (450.0, 365.0));
+
+    let tuning = GameTuning { enemy_shoot_interval: 2.0, level_waves: [vec![2], vec![3], vec![4]], ..default() };
+    let easy = SimRules::new(&tuning, Difficulty::Easy.modifiers());
+    assert_eq!(easy.shoot_frames, 180);
+    assert_eq!(easy.level_waves, [vec![1], vec![2], vec![2]]);
+    assert_eq!(SimState::new(1, easy).enemies.len(), 1);
+}
+
+#[test]
+fn authored_waves_and_armored_enemies_drive_the_simulation() {
+    let armored = EnemySpawn { kind: EnemyKind::Armored, ..EnemySpawn::at(0.0, 200.0) };
+    let waves = vec![
+        Wave { enemies: vec![armored], ..default() },
+        Wave { at: Some(1.0), enemies: vec![EnemySpawn::at(100.0, 150.0)], ..default() },
+    ];
+    let rules = SimRules::default().with_layouts([Some(waves), None, None]);
+    let mut state = SimState::new(3, rules.clone());
+    assert_eq!(state.enemies.len(), 1);
+    assert_eq!(state.enemies[0].health, 2);
+
+    for hit in 0..2 {
+        let pos = Vec2::new(0.0, 205.0);
+        state.player_lasers.push(SimLaser { id: 1000 + hit, owner: 0, pos, prev: pos });
+        state.step([NetInput::default(); NET_PLAYERS]);
+    }
+    assert!(state.enemies.is_empty());
+    assert_eq!(state.players[0].score, rules.enemy_points);
+    assert_eq!(state.level, 1);
+
+    for _ in 0..60 {
+        state.step([NetInput::default(); NET_PLAYERS]);
+    }
+    assert_eq!(state.enemies.len(), 1);
+    assert_eq!(state.enemies[0].origin, Vec2::new(100.0, 150.0));
+
+    let definitions = EnemyDefinitions([(EnemyKind::Armored, EnemyDefinition { health: Some(5), tint: None })].into());
+    assert_eq!(rules.with_enemy_definitions(&definitions).enemy_health(EnemyKind::Armored), 5);
+}
+
+#[test]
+fn late_remote_inputs_are_rolled_back_into_the_same_state() {
+    let mut reference = SimState::new(7, SimRules::default());
+    let mut rollback = Rollback::new(0, 7, SimRules::default());
+    let delay = rollback::INPUT_DELAY;
+
+    let mut remote_frame = delay;
+    for frame in 0..300 {
+        while rollback.frame() <= frame && rollback.can_advance() {
+            rollback.add_local_input(scripted(0, rollback.frame() + delay));
+            rollback.advance();
+        }
+        if frame % 8 == 7 {
+            while remote_frame <= rollback.frame() {
+                rollback.add_remote_input(remote_frame, scripted(1, remote_frame));
+                remote_frame += 1;
+            }
+        }
+    }
+    while remote_frame < rollback.frame() + delay {
+        rollback.add_remote_input(remote_frame, scripted(1, remote_frame));
+        remote_frame += 1;
+    }
+    rollback.apply_rollback();
+
+    for frame in 0..rollback.frame() {
+        let input = |player| if frame < delay { NetInput::default() } else { scripted(player, frame) };
+        reference.step([input(0), input(1)]);
+    }
+
+    assert!(rollback.rollbacks > 0);
+    assert_eq!(rollback.state.checksum(), reference.checksum());
+}
+
+#[test]
+fn two_peers_over_loopback_stay_in_sync_with_loss_and_latency() {
+    let conditions = NetConditions { loss: 0.2, latency: Duration::from_millis(30) };
+    let rules = SimRules::new(&GameTuning::default(), Difficulty::Hard.modifiers());
+    let mut host = NetSession::start(&NetConfig { conditions, ..NetConfig::host(0) }, rules.clone()).unwrap();
+    let port = host.local_addr().unwrap().port();
+    let address: SocketAddr = format!("127.0.0.1:{port}").parse().unwrap();
+    let mut guest = NetSession::start(&NetConfig { conditions, ..NetConfig::join(address, 0) }, SimRules::default()).unwrap();
+
+    let target = 240;
+    let deadline = Instant::now() + Duration::from_secs(30);
+    let done = |s: &NetSession| s.rollback.as_ref().is_some_and(|r| r.frame() >= target && r.is_confirmed());
+    while !(done(&host) && done(&guest)) {
+        assert!(Instant::now() < deadline, "peers did not reach frame {target}");
+        for (player, session) in [(0, &mut host), (1, &mut guest)] {
+            match session.rollback.as_ref().map(|r| r.frame()) {
+                Some(frame) if frame < target => {
+                    session.tick(scripted(player, frame));
+                }
+                _ => {
+                    session.poll();
+                    session.send_inputs();
+                }
+            }
+        }
+        sleep(Duration::from_millis(1));
+    }
+
+    let host_state = &host.rollback.as_ref().unwrap().state;
+    let guest_state = &guest.rollback.as_ref().unwrap().state;
+    assert_eq!(guest_state.rules, rules);
+    assert_eq!(host_state.frame, guest_state.frame);
+    assert_eq!(host_state.checksum(), guest_state.checksum());
+}
+
+#[test]
+fn lost_peer_returns_to_net_menu_with_a_notice() {
+    let mut host = NetSession::start(&NetConfig::host(0), SimRules::default()).unwrap();
+    let address: SocketAddr = format!("127.0.0.1:{}", host.local_addr().unwrap().port()).parse().unwrap();
+    let mut app = app();
+    press(&mut app, KeyCode::KeyN);
+    app.insert_resource(NetSession::start(&NetConfig::join(address, 0), SimRules::default()).unwrap());
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while state::<GameState>(&app) != GameState::Online {
+        assert!(Instant::now() < deadline, "guest did not connect");
+        host.poll();
+        app.update();
+        sleep(Duration::from_millis(1));
+    }
+    drop(host);
+    sleep(Duration::from_millis(5200));
+    app.update();
+    app.update();
+
+    assert_eq!(state::<GameState>(&app), GameState::NetMenu);
+    assert_eq!(app.world().resource::<NetMenu>().status, NET_CONNECTION_LOST);
+    assert!(app.world().get_resource::<NetSession>().is_none());
+}
+
+#[test]
+fn command_line_flags_configure_the_session() {
+    let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<_>>();
+
+    assert_eq!(NetConfig::from_args(args("")), Ok(None));
+    let host = NetConfig::from_args(args("--host 7000 --loss 0.1 --latency 80")).unwrap().unwrap();
+    assert_eq!(host.role, NetRole::Host);
+    assert_eq!(host.bind.port(), 7000);
+    assert_eq!(host.conditions.loss, 0.1);
+    assert_eq!(host.conditions.latency, Duration::from_millis(80));
+
+    let join = NetConfig::from_args(args("--join 127.0.0.1:7000 --port 7001")).unwrap().unwrap();
+    assert_eq!(join.role, NetRole::Join("127.0.0.1:7000".parse().unwrap()));
+    assert_eq!(join.bind.port(), 7001);
+
+    assert!(NetConfig::from_args(args("--join nowhere")).is_err());
+}
+
+#[test]
+fn n_opens_net_menu_and_escape_returns() {
+    let mut app = app();
+
+    press(&mut app, KeyCode::KeyN);
+    assert_eq!(state::<GameState>(&app), GameState::NetMenu);
+
+    press(&mut app, KeyCode::Escape);
+    assert_eq!(state::<GameState>(&app), GameState::NotStarted);
+    assert!(app.world().get_resource::<NetSession>().is_none());
+}
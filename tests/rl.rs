@@ -0,0 +1,107 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::thread;
+use space_shooter::rl::*;
+
+fn config(seed: u64) -> RlConfig {
+    RlConfig { seed: Some(seed), ..RlConfig::default() }
+}
+
+#[test]
+fn reset_observes_player_and_enemies() {
+    let (_, result) = RlEnv::reset(config(1));
+
+    let Observation::Positions { player, enemies, .. } = result.observation else {
+        panic!("expected positions observation");
+    };
+    assert!(player.is_some());
+    assert!(!enemies.is_empty());
+    assert_eq!(result.reward, 0.0);
+    assert!(!result.done);
+    assert_eq!(result.info.level, 1);
+}
+
+#[test]
+fn same_seed_and_actions_replay_identically() {
+    let run = |seed| {
+        let (mut env, _) = RlEnv::reset(config(seed));
+        (0..40).map(|i| {
+            let result = env.step(RlAction { left: i % 3 == 0, right: i % 5 == 0, fire: i % 2 == 0 });
+            (result.observation, result.reward)
+        }).collect::<Vec<_>>()
+    };
+
+    assert_eq!(run(4), run(4));
+}
+
+#[test]
+fn idle_agent_dies_with_death_penalty() {
+    let (mut env, _) = RlEnv::reset(RlConfig { frame_skip: 8, ..config(2) });
+
+    let mut last = None;
+    for _ in 0..2000 {
+        let result = env.step(RlAction::default());
+        if result.done {
+            last = Some(result);
+            break;
+        }
+    }
+
+    let last = last.expect("idle agent should eventually be shot");
+    assert!(!last.info.won);
+    assert!(last.reward <= RlConfig::default().death_penalty);
+    assert!(env.step(RlAction::default()).done);
+}
+
+#[test]
+fn grid_observation_marks_player_cell() {
+    let (mut env, _) = RlEnv::reset(RlConfig { obs: ObservationKind::Grid, ..config(3) });
+    let mut result = env.step(RlAction::default());
+    for _ in 0..15 {
+        result = env.step(RlAction::default());
+    }
+
+    let Observation::Grid { width, height, cells } = result.observation else {
+        panic!("expected grid observation");
+    };
+    assert_eq!(cells.len(), width * height);
+    assert_eq!(cells.iter().filter(|c| **c == CELL_PLAYER).count(), 1);
+    assert!(cells.contains(&CELL_ENEMY));
+    assert_eq!(grid_index(-500.0, 400.0), Some(0));
+    assert_eq!(grid_index(600.0, 0.0), None);
+}
+
+#[test]
+fn json_protocol_round_trip_over_tcp() {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let address = listener.local_addr().unwrap();
+    let server = thread::spawn(move || {
+        let (stream, _) = listener.accept().unwrap();
+        serve_connection(stream).unwrap();
+    });
+
+    let stream = TcpStream::connect(address).unwrap();
+    let mut writer = stream.try_clone().unwrap();
+    let mut reader = BufReader::new(stream);
+    let mut request = |line: &str| -> serde_json::Value {
+        writeln!(writer, "{line}").unwrap();
+        let mut response = String::new();
+        reader.read_line(&mut response).unwrap();
+        serde_json::from_str(&response).unwrap()
+    };
+
+    assert!(request(r#"{"cmd":"step"}"#)["error"].is_string());
+    assert!(request("not json")["error"].is_string());
+    assert_eq!(request(r#"{"cmd":"spec"}"#)["grid"]["width"], GRID_WIDTH);
+
+    let reset = request(r#"{"cmd":"reset","seed":5,"frame_skip":2}"#);
+    assert_eq!(reset["done"], false);
+    assert!(reset["observation"]["player"].is_array());
+
+    let step = request(r#"{"cmd":"step","action":{"right":true,"fire":true}}"#);
+    assert_eq!(step["info"]["steps"], 1);
+    assert!(step["reward"].is_number());
+
+    assert_eq!(request(r#"{"cmd":"close"}"#)["closed"], true);
+    server.join().unwrap();
+}
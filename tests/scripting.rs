@@ -0,0 +1,113 @@
+mod common;
+
+use bevy::prelude::*;
+use space_shooter::game::GameState;
+use space_shooter::lasers_enemies::LaserVelocity;
+use space_shooter::level_file::*;
+use space_shooter::levels::*;
+use space_shooter::scripting::*;
+use space_shooter::waves::Wave;
+use common::*;
+
+fn with_script(app: &mut App, path: &str, source: &str) -> Handle<Script> {
+    let handle = app.world_mut().resource_mut::<Assets<Script>>().add(Script { source: source.to_string() });
+    app.world_mut().resource_mut::<ScriptHost>().handles.insert(path.to_string(), handle.clone());
+    handle
+}
+
+fn with_level(app: &mut App, script: Option<&str>, enemies: Vec<EnemySpawn>) {
+    let level = LevelFile {
+        script: script.map(String::from),
+        waves: vec![Wave { enemies, ..Wave::default() }],
+    };
+    let handle = app.world_mut().resource_mut::<Assets<LevelFile>>().add(level);
+    app.world_mut().resource_mut::<LevelFiles>().levels = vec![handle];
+}
+
+fn error_text(app: &mut App) -> String {
+    app.world_mut().query_filtered::<&Text, With<ScriptErrorText>>()
+        .single(app.world())
+        .unwrap()
+        .0
+        .clone()
+}
+
+#[test]
+fn level_script_spawns_enemies_and_fires() {
+    let mut app = app();
+    with_script(&mut app, "events.rhai", r#"
+        fn update() {
+            if level_time() > 0.5 && !("done" in this) {
+                this.done = true;
+                spawn_enemy(0.0, 250.0, "armored");
+                fire(player_x(), 0.0, 0.0, -100.0);
+            }
+        }
+    "#);
+    with_level(&mut app, Some("events.rhai"), vec![EnemySpawn::at(-300.0, 200.0)]);
+    press(&mut app, KeyCode::KeyS);
+
+    advance(&mut app, 1.0);
+    assert_eq!(count::<EnemiesStructInLevel1>(&mut app), 2);
+    assert_eq!(count::<LaserVelocity>(&mut app), 1);
+    assert!(positions::<LaserVelocity>(&mut app)[0].y < 0.0);
+    assert!(app.world().resource::<ScriptHost>().errors.is_empty());
+}
+
+#[test]
+fn enemy_script_moves_its_enemy() {
+    let mut app = app();
+    with_script(&mut app, "drift.rhai", r#"
+        fn update() {
+            this.x += 60.0 * delta();
+        }
+    "#);
+    with_level(&mut app, None, vec![
+        EnemySpawn { script: Some("drift.rhai".to_string()), ..EnemySpawn::at(0.0, 200.0) },
+    ]);
+    press(&mut app, KeyCode::KeyS);
+
+    advance(&mut app, 1.0);
+    let x = positions::<ScriptedEnemy>(&mut app)[0].x;
+    assert!((55.0..65.0).contains(&x), "{x}");
+}
+
+#[test]
+fn script_errors_are_shown_and_cleared_on_reload() {
+    let mut app = app();
+    let handle = with_script(&mut app, "broken.rhai", "fn update() { this.x = ; }");
+    with_level(&mut app, Some("broken.rhai"), vec![EnemySpawn::at(-300.0, 200.0)]);
+    press(&mut app, KeyCode::KeyS);
+    advance(&mut app, 0.2);
+
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert!(error_text(&mut app).starts_with("scripts/broken.rhai"));
+
+    app.world_mut().resource_mut::<Assets<Script>>().get_mut(&handle).unwrap().source =
+        "fn update() { missing(); }".to_string();
+    advance(&mut app, 0.2);
+    assert!(error_text(&mut app).contains("missing"));
+
+    app.world_mut().resource_mut::<Assets<Script>>().get_mut(&handle).unwrap().source =
+        r#"fn update() { if !("done" in this) { this.done = true; spawn_enemy(300.0, 200.0); } }"#.to_string();
+    advance(&mut app, 0.2);
+    assert_eq!(error_text(&mut app), "");
+    assert_eq!(count::<EnemiesStructInLevel1>(&mut app), 2);
+}
+
+#[test]
+fn shipped_scripts_load_and_compile() {
+    let mut app = app();
+    let paths = ["reinforcements.rhai", "zigzag.rhai"];
+    {
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        let mut host = app.world_mut().resource_mut::<ScriptHost>();
+        for path in paths {
+            host.load(path, &asset_server);
+        }
+    }
+    wait_for_asset(&mut app, |world| paths.iter().all(|path| world.resource::<ScriptHost>().is_ready(path)));
+
+    let host = app.world().resource::<ScriptHost>();
+    assert!(paths.iter().all(|path| host.is_ready(path)), "{:?}", host.errors);
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use bevy::prelude::*;
+use space_shooter::headless::HEADLESS_STEP;
+use space_shooter::levels::*;
+use space_shooter::physics::PhysicalTranslation;
+use space_shooter::tuning::*;
+use space_shooter::waves::WaveDirector;
+use common::*;
+
+fn loaded_tuning(app: &mut App) -> Handle<GameTuning> {
+    let handle = app.world().resource::<TuningHandle>().0.clone();
+    wait_for_asset(app, |world| world.resource::<Assets<GameTuning>>().contains(&handle));
+    handle
+}
+
+#[test]
+fn shipped_tuning_file_matches_defaults() {
+    let bytes = std::fs::read(concat!(env!("CARGO_MANIFEST_DIR"), "/assets/", "game.tuning.ron")).unwrap();
+    assert_eq!(GameTuning::from_ron(&bytes).unwrap(), GameTuning::default());
+
+    let partial = GameTuning::from_ron(b"(enemy_shoot_interval: 1.0)").unwrap();
+    assert_eq!(partial, GameTuning { enemy_shoot_interval: 1.0, ..GameTuning::default() });
+    assert!(GameTuning::from_ron(b"(enemy_spacing: (1.0, 2.0))").is_err());
+}
+
+#[test]
+fn edited_tuning_takes_effect_while_running() {
+    let mut app = app();
+    let handle = loaded_tuning(&mut app);
+
+    {
+        let mut assets = app.world_mut().resource_mut::<Assets<GameTuning>>();
+        let tuning = assets.get_mut(&handle).unwrap();
+        tuning.player_laser_speed = 900.0;
+        tuning.enemy_shoot_interval = 1.0;
+        tuning.level_waves[0] = vec![2];
+    }
+    app.update();
+    app.update();
+    assert_eq!(app.world().resource::<GameTuning>().player_laser_speed, 900.0);
+    assert_eq!(app.world().resource::<EnemyShootTimer>().0.duration().as_secs_f32(), 1.0);
+
+    press(&mut app, KeyCode::KeyS);
+    assert_eq!(app.world().resource::<WaveDirector>().total_enemies(), 2);
+
+    let laser = spawn_player_laser(&mut app, Vec2::new(0.0, -300.0));
+    app.update();
+    let y = app.world().get::<PhysicalTranslation>(laser).unwrap().0.y;
+    assert!((y - (-300.0 + 900.0 * HEADLESS_STEP)).abs() < 0.01, "{y}");
+}
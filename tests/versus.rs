@@ -0,0 +1,96 @@
+mod common;
+
+use bevy::prelude::*;
+use space_shooter::game::GameState;
+use space_shooter::lasers_enemies::LasersEnemiesStruct;
+use space_shooter::lasers_player::LasersPlayerStruct;
+use space_shooter::levels::*;
+use space_shooter::player::*;
+use space_shooter::stats::Lives;
+use space_shooter::versus::*;
+use common::*;
+
+fn duel() -> App {
+    let mut app = app();
+    press(&mut app, KeyCode::KeyV);
+    app
+}
+
+fn top_ship(app: &mut App) -> (Entity, Vec2) {
+    let mut players = app.world_mut().query::<(Entity, &PlayerSide, &Transform)>();
+    players.iter(app.world())
+        .find(|(_, side, _)| **side == PlayerSide::Top)
+        .map(|(e, _, t)| (e, t.translation.truncate()))
+        .unwrap()
+}
+
+fn win_round_for_bottom(app: &mut App) {
+    for _ in 0..VERSUS_LIVES {
+        let (_, at) = top_ship(app);
+        spawn_player_laser(app, at);
+        app.update();
+    }
+}
+
+#[test]
+fn v_starts_duel_with_cover_and_no_enemies() {
+    let mut app = duel();
+
+    assert_eq!(state::<GameState>(&app), GameState::InGame);
+    assert_eq!(count::<PlayerStruct>(&mut app), 2);
+    assert_eq!(count::<EnemiesStructInLevel1>(&mut app), 0);
+    assert_eq!(count::<CoverStruct>(&mut app), 24);
+    assert_eq!(app.world().resource::<VersusMatch>().best_of, 3);
+
+    let (top, _) = top_ship(&mut app);
+    assert_eq!(app.world().get::<Lives>(top).unwrap().0, VERSUS_LIVES);
+}
+
+#[test]
+fn top_player_fires_downward() {
+    let mut app = duel();
+
+    press(&mut app, KeyCode::Enter);
+
+    assert_eq!(count::<LasersEnemiesStruct>(&mut app), 1);
+    assert_eq!(count::<LasersPlayerStruct>(&mut app), 0);
+    let before = positions::<LasersEnemiesStruct>(&mut app)[0].y;
+    advance(&mut app, 0.2);
+    assert!(positions::<LasersEnemiesStruct>(&mut app)[0].y < before);
+}
+
+#[test]
+fn cover_absorbs_lasers_and_breaks() {
+    let mut app = duel();
+    let block = positions::<CoverStruct>(&mut app)[0];
+
+    for hit in 1..=COVER_HEALTH {
+        spawn_player_laser(&mut app, Vec2::new(block.x, block.y - 20.0));
+        advance(&mut app, 0.2);
+        assert_eq!(count::<LasersPlayerStruct>(&mut app), 0);
+        assert_eq!(count::<CoverStruct>(&mut app), if hit < COVER_HEALTH { 24 } else { 23 });
+    }
+}
+
+#[test]
+fn rounds_are_scored_until_best_of_is_decided() {
+    let mut app = duel();
+
+    win_round_for_bottom(&mut app);
+    assert_eq!(app.world().resource::<VersusMatch>().wins, [1, 0]);
+    assert_eq!(app.world().resource::<VersusMatch>().round_winner, Some(0));
+
+    advance(&mut app, ROUND_OVER_SECONDS + 0.5);
+    let versus = app.world().resource::<VersusMatch>();
+    assert_eq!(versus.round, 2);
+    assert!(versus.round_over.is_none());
+    let (top, _) = top_ship(&mut app);
+    assert_eq!(app.world().get::<Lives>(top).unwrap().0, VERSUS_LIVES);
+    assert!(app.world().get::<Downed>(top).is_none());
+
+    win_round_for_bottom(&mut app);
+    advance(&mut app, ROUND_OVER_SECONDS + 0.5);
+
+    assert_eq!(app.world().resource::<VersusMatch>().winner(), Some(0));
+    assert_eq!(state::<GameState>(&app), GameState::Win);
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use bevy::prelude::*;
+use space_shooter::lasers_enemies::LasersEnemiesStruct;
+use space_shooter::level_file::*;
+use space_shooter::levels::*;
+use space_shooter::waves::*;
+use common::*;
+
+fn wave(size: usize, at: Option<f32>, cleared: Option<f32>) -> Wave {
+    Wave {
+        at,
+        cleared,
+        entry: EntryPath::InPlace,
+        enemies: (0..size).map(|i| EnemySpawn::at(i as f32 * 100.0, 200.0)).collect(),
+    }
+}
+
+fn director(waves: Vec<Wave>) -> WaveDirector {
+    let mut director = WaveDirector::default();
+    director.begin(LevelState::Level1, waves);
+    director
+}
+
+#[test]
+fn later_waves_start_on_time_or_when_previous_is_partly_cleared() {
+    let mut timed = director(vec![wave(2, None, None), wave(3, Some(5.0), Some(0.5))]);
+    assert_eq!(timed.tick(0.0, &[]).len(), 2);
+    assert!(timed.tick(4.0, &[2]).is_empty());
+    assert_eq!(timed.tick(1.0, &[2]).len(), 3);
+    assert!(timed.all_spawned());
+
+    let mut cleared = director(vec![wave(2, None, None), wave(3, Some(5.0), Some(0.5))]);
+    cleared.tick(0.0, &[]);
+    assert_eq!(cleared.tick(0.1, &[1]).len(), 3);
+}
+
+#[test]
+fn wave_without_trigger_waits_for_previous_to_be_destroyed() {
+    let mut director = director(vec![wave(2, None, None), wave(1, None, None)]);
+    director.tick(0.0, &[]);
+
+    assert!(director.tick(60.0, &[1]).is_empty());
+    assert!(!director.is_cleared(1));
+    assert_eq!(director.tick(0.1, &[0]).len(), 1);
+    assert!(director.is_cleared(0));
+}
+
+#[test]
+fn staggered_spawns_count_as_remaining() {
+    let mut staggered = wave(2, None, None);
+    staggered.enemies[1].delay = 3.0;
+    let mut director = director(vec![staggered, wave(1, None, Some(1.0))]);
+
+    assert_eq!(director.tick(0.0, &[]).len(), 1);
+    assert!(director.tick(0.1, &[0]).is_empty());
+    assert_eq!(director.tick(3.0, &[0]).len(), 1);
+    assert_eq!(director.tick(0.1, &[0, 0]).len(), 1);
+}
+
+#[test]
+fn enemies_fly_in_from_edge_and_hold_fire_until_in_formation() {
+    let mut app = app();
+    let level = LevelFile {
+        waves: vec![Wave {
+            entry: EntryPath::Left,
+            ..wave(1, None, None)
+        }],
+        ..LevelFile::default()
+    };
+    let handle = app.world_mut().resource_mut::<Assets<LevelFile>>().add(level);
+    app.world_mut().resource_mut::<LevelFiles>().levels = vec![handle];
+    press(&mut app, KeyCode::KeyS);
+
+    let start = positions::<EnemiesStructInLevel1>(&mut app)[0];
+    assert!(start.x < -500.0);
+    assert_eq!(count::<EnemyEntry>(&mut app), 1);
+    app.world_mut().resource_mut::<EnemyShootTimer>().0.almost_finish();
+    app.update();
+    assert_eq!(count::<LasersEnemiesStruct>(&mut app), 0);
+
+    advance(&mut app, 2.5);
+    assert_eq!(count::<EnemyEntry>(&mut app), 0);
+    assert_eq!(positions::<EnemiesStructInLevel1>(&mut app)[0].truncate(), Vec2::new(0.0, 200.0));
+}